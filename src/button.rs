@@ -3,6 +3,7 @@
 //! See [Button] for more info.
 
 use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
 use crate::ui::{GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
 use core::ops::Add;
@@ -128,6 +129,28 @@ impl<'a> Button<'a> {
     }
 }
 
+impl Button<'_> {
+    /// Size of the button: the label plus padding and border, at least as high as the default widget height
+    fn size<COL: PixelColor>(&self, style: &Style<COL>) -> Size {
+        let text = Text::new(
+            self.label,
+            Point::new(0, 0),
+            MonoTextStyle::new(&style.default_font, style.text_color),
+        );
+        let size = text.bounding_box().size;
+        let padding = style.spacing.button_padding;
+        let border = style.border_width;
+
+        Size::new(
+            size.width + 2 * padding.width + 2 * border,
+            max(
+                size.height + 2 * padding.height + 2 * border,
+                style.default_widget_height,
+            ),
+        )
+    }
+}
+
 impl Widget for Button<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
@@ -142,16 +165,11 @@ impl Widget for Button<'_> {
             MonoTextStyle::new(&font, ui.style().text_color),
         );
 
-        let height = ui.style().default_widget_height;
-        let size = text.bounding_box();
         let padding = ui.style().spacing.button_padding;
         let border = ui.style().border_width;
 
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(
-            size.size.width + 2 * padding.width + 2 * border,
-            max(size.size.height + 2 * padding.height + 2 * border, height),
-        ))?;
+        let iresponse = ui.allocate_focusable_space(self.size(ui.style()))?;
 
         // move text
         text.translate_mut(iresponse.area.top_left.add(Point::new(
@@ -219,4 +237,8 @@ impl Widget for Button<'_> {
 
         Ok(Response::new(iresponse).set_clicked(click).set_down(down))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        Some(self.size(style))
+    }
 }
@@ -10,6 +10,7 @@
 //! with the framework's [Smartstate] system for efficient rendering.
 //!
 use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
 use crate::ui::{GuiError, GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
 use core::ops::{Add, Sub};
@@ -154,7 +155,7 @@ impl Widget for Checkbox<'_> {
             let biggest_pad = max(pad.width, pad.height);
             Size::new(biggest_pad, biggest_pad)
         };
-        let iresponse = ui.allocate_space(Size::new(size, size))?;
+        let iresponse = ui.allocate_focusable_space(Size::new(size, size))?;
 
         // check interaction
 
@@ -249,4 +250,10 @@ impl Widget for Checkbox<'_> {
 
         Ok(Response::new(iresponse).set_changed(changed))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        // the checkbox is square, and grows with the row height
+        let size = style.default_widget_height;
+        Some(Size::new(size, size))
+    }
 }
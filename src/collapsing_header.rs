@@ -0,0 +1,388 @@
+//! # Collapsing Header
+//!
+//! A [CollapsingHeader] is a section with a clickable header row, whose body is only shown while the
+//! section is open. A typical use is a long settings screen, split into sections like "Network" and "Display".
+//!
+//! ## Core Features
+//!
+//! - Header row with a chevron icon showing whether the section is open
+//! - The open state is kept in a caller-owned `bool`, so it persists between frames
+//! - The body closure only runs while the section is open
+//! - Smartstate support: when the section is toggled, everything below the header is cleared and redrawn
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::checkbox::Checkbox;
+//! # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+//! # use kolibri_embedded_gui::collapsing_header::CollapsingHeader;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! // keep the open state around between frames
+//! let mut network_open = false;
+//! let mut dhcp = true;
+//! let mut smartstates = SmartstateProvider::<20>::new();
+//!
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     smartstates.restart_counter();
+//!
+//!     CollapsingHeader::new("Network", &mut network_open)
+//!         .show_with_smartstates(&mut ui, &mut smartstates, |ui, smartstates| {
+//!             ui.add_horizontal(Label::new("DHCP").smartstate(smartstates.nxt()));
+//!             ui.add(Checkbox::new(&mut dhcp).smartstate(smartstates.nxt()));
+//!             Ok(())
+//!         })
+//!         .unwrap();
+//!
+//!     ui.add(Label::new("Below the section").smartstate(smartstates.nxt()));
+//!     # break;
+//! }
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! The header takes up a full row of the [Ui], and the body is drawn in a [Ui::sub_ui] below it.
+//! Toggling the section moves everything below the header, so the area below the header is cleared,
+//! and with [CollapsingHeader::show_with_smartstates], all smartstates after the header's are forced to
+//! redraw (like the keyboard helper does when it opens or closes).
+//!
+//! The header uses two smartstates: one for drawing the header, and one for tracking whether the
+//! section is open, so changes of the open state from outside are noticed as well.
+
+use crate::smartstate::{Smartstate, SmartstateProvider};
+use crate::ui::{GuiError, GuiResult, Interaction, Response, Ui};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::image::Image;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle};
+use embedded_graphics::text::{Baseline, Text};
+use embedded_iconoir::prelude::*;
+use embedded_iconoir::{size12px, size18px, size24px};
+
+/// Section with a header row that can be clicked to show or hide its body.
+/// See the [module docs](crate::collapsing_header) for details.
+pub struct CollapsingHeader<'a> {
+    label: &'a str,
+    open: &'a mut bool,
+}
+
+impl<'a> CollapsingHeader<'a> {
+    /// Creates a new collapsing header with the given label, with `open` being whether the section is open.
+    pub fn new(label: &'a str, open: &'a mut bool) -> Self {
+        CollapsingHeader { label, open }
+    }
+
+    /// Draws the header, and the body using `body` if the section is open.
+    ///
+    /// `body` gets a sub-[Ui] below the header.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the header. It's `clicked` and `changed` if the section was toggled this frame.
+    pub fn show<DRAW, COL, F>(mut self, ui: &mut Ui<DRAW, COL>, body: F) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let (response, _) = self.header(ui, None::<&mut SmartstateProvider>)?;
+        if response.changed() {
+            let below = ui.free_area();
+            ui.clear_area(below)?;
+        }
+        if *self.open {
+            ui.sub_ui(body)?;
+        }
+        Ok(response)
+    }
+
+    /// Like [CollapsingHeader::show], but the header uses smartstates from `smartstates`, and `body` gets
+    /// the provider as well.
+    ///
+    /// When the section is toggled, everything below the header is cleared, and all smartstates after the
+    /// header's are forced to redraw.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the header. It's `clicked` and `changed` if the section was toggled this frame.
+    pub fn show_with_smartstates<DRAW, COL, F, const N: usize>(
+        mut self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: &mut SmartstateProvider<N>,
+        body: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        let (response, open_state) = self.header(ui, Some(smartstates))?;
+
+        // the section was toggled, either by the header or from outside
+        if !open_state.is_state(*self.open as u32) {
+            smartstates.force_redraw_remaining();
+            let below = ui.free_area();
+            ui.clear_area(below)?;
+        }
+        if *self.open {
+            ui.sub_ui(|ui| body(ui, smartstates))?;
+        }
+        Ok(response)
+    }
+
+    /// Draws the header row, toggling the section if it was clicked.
+    ///
+    /// ## Returns
+    ///
+    /// The [Response] of the header, and the state of the smartstate tracking whether the section is open
+    /// (from the last frame, or empty without smartstates).
+    fn header<DRAW, COL, const N: usize>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: Option<&mut SmartstateProvider<N>>,
+    ) -> GuiResult<(Response, Smartstate)>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let style = *ui.style();
+        let font = style.default_font;
+        let mut text = Text::new(
+            self.label,
+            Point::zero(),
+            MonoTextStyle::new(&font, style.text_color),
+        );
+        text.text_style.baseline = Baseline::Top;
+        let text_height = font.character_size.height;
+        let padding = style.spacing.button_padding;
+        let height = max(
+            text_height + 2 * padding.height,
+            style.default_widget_height,
+        );
+
+        let width = ui.space_available().width;
+        let iresponse = ui.allocate_space(Size::new(width, height))?;
+        ui.new_row();
+
+        let clicked = matches!(iresponse.interaction, Interaction::Release(_));
+        if clicked {
+            *self.open = !*self.open;
+        }
+
+        let interaction_state = match iresponse.interaction {
+            Interaction::Click(_) | Interaction::Drag(_) | Interaction::Release(_) => 1,
+            Interaction::Hover(_) => 2,
+            _ => 0,
+        };
+
+        let (redraw, open_state) = match smartstates {
+            Some(smartstates) => {
+                let state = Smartstate::state(((*self.open as u32) << 2) | interaction_state);
+                let smartstate = smartstates.nxt();
+                let redraw = *smartstate != state;
+                *smartstate = state;
+
+                let open_smartstate = smartstates.nxt();
+                let open_state = *open_smartstate;
+                open_smartstate.set_state(*self.open as u32);
+                (redraw, open_state)
+            }
+            None => (true, Smartstate::empty()),
+        };
+
+        if redraw {
+            let area = iresponse.area;
+            let fill_color = match interaction_state {
+                1 => style.primary_color,
+                2 => style.highlight_item_background_color,
+                _ => style.background_color,
+            };
+
+            ui.start_drawing(&area);
+            ui.draw(&area.into_styled(PrimitiveStyleBuilder::new().fill_color(fill_color).build()))
+                .map_err(|_| GuiError::DrawError(Some("Couldn't draw collapsing header")))?;
+
+            // chevron, sized to match the text
+            let icon_color = style.icon_color;
+            let icon_width = match (text_height, *self.open) {
+                (0..=17, true) => draw_chevron(
+                    ui,
+                    size12px::navigation::NavArrowDown::new(icon_color),
+                    area,
+                    padding.width,
+                ),
+                (0..=17, false) => draw_chevron(
+                    ui,
+                    size12px::navigation::NavArrowRight::new(icon_color),
+                    area,
+                    padding.width,
+                ),
+                (18..=23, true) => draw_chevron(
+                    ui,
+                    size18px::navigation::NavArrowDown::new(icon_color),
+                    area,
+                    padding.width,
+                ),
+                (18..=23, false) => draw_chevron(
+                    ui,
+                    size18px::navigation::NavArrowRight::new(icon_color),
+                    area,
+                    padding.width,
+                ),
+                (_, true) => draw_chevron(
+                    ui,
+                    size24px::navigation::NavArrowDown::new(icon_color),
+                    area,
+                    padding.width,
+                ),
+                (_, false) => draw_chevron(
+                    ui,
+                    size24px::navigation::NavArrowRight::new(icon_color),
+                    area,
+                    padding.width,
+                ),
+            }?;
+
+            text.translate_mut(
+                area.top_left
+                    + Point::new(
+                        (2 * padding.width + icon_width) as i32,
+                        (area.size.height.saturating_sub(text_height) / 2) as i32,
+                    ),
+            );
+            ui.draw(&text)
+                .map_err(|_| GuiError::DrawError(Some("Couldn't draw collapsing header")))?;
+            ui.finalize()?;
+        }
+
+        let response = Response::new(iresponse)
+            .set_clicked(clicked)
+            .set_changed(clicked)
+            .set_down(interaction_state == 1)
+            .set_redraw(redraw);
+        Ok((response, open_state))
+    }
+}
+
+/// Draws the chevron `icon` at the left of the header `area`, vertically centered.
+///
+/// ## Returns
+///
+/// The width of the icon.
+fn draw_chevron<DRAW, COL>(
+    ui: &mut Ui<DRAW, COL>,
+    icon: impl ImageDrawable<Color = COL>,
+    area: Rectangle,
+    padding: u32,
+) -> GuiResult<u32>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    let size = icon.bounding_box().size;
+    let pos = area.top_left
+        + Point::new(
+            padding as i32,
+            (area.size.height.saturating_sub(size.height) / 2) as i32,
+        );
+    ui.draw(&Image::new(&icon, pos))
+        .map_err(|_| GuiError::DrawError(Some("Couldn't draw collapsing header icon")))?;
+    Ok(size.width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label::Label;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_collapsing_header_toggle() {
+        let mut open = false;
+
+        for (interaction, expect_open) in [
+            (Interaction::None, false),
+            (Interaction::Release(Point::new(10, 10)), true),
+            (Interaction::None, true),
+            (Interaction::Release(Point::new(10, 10)), false),
+        ] {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.interact(interaction);
+
+            let mut body = None;
+            let response = CollapsingHeader::new("Net", &mut open)
+                .show(&mut ui, |ui| {
+                    body = Some(ui.add(Label::new("A")).internal.area);
+                    Ok(())
+                })
+                .unwrap();
+
+            assert_eq!(response.internal.area.top_left, Point::new(3, 3));
+            assert_eq!(response.changed(), interaction != Interaction::None);
+            // the body only runs while the section is open, below the header
+            match body {
+                Some(body) => {
+                    assert!(expect_open);
+                    assert!(body.top_left.y > response.internal.area.bottom_right().unwrap().y);
+                }
+                None => assert!(!expect_open),
+            }
+        }
+        assert!(!open);
+    }
+
+    #[test]
+    fn test_collapsing_header_redraw_below_on_toggle() {
+        let mut smartstates = SmartstateProvider::<5>::new();
+
+        // draws a frame, returning whether the widget below the section had to be redrawn
+        let mut frame = |open: &mut bool, interaction: Interaction| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.interact(interaction);
+            smartstates.restart_counter();
+
+            CollapsingHeader::new("Net", open)
+                .show_with_smartstates(&mut ui, &mut smartstates, |ui, smartstates| {
+                    ui.add(Label::new("A").smartstate(smartstates.nxt()));
+                    Ok(())
+                })
+                .unwrap();
+            let redraw = *smartstates.peek() != Smartstate::state(0);
+            // a widget below the section (there's no space left for a real one)
+            smartstates.nxt().set_state(0);
+            redraw
+        };
+
+        let mut open = false;
+        assert!(frame(&mut open, Interaction::None));
+        assert!(!frame(&mut open, Interaction::None));
+
+        // toggled by clicking the header
+        assert!(frame(&mut open, Interaction::Release(Point::new(10, 10))));
+        assert!(open);
+        assert!(!frame(&mut open, Interaction::None));
+
+        // toggled from outside
+        open = false;
+        assert!(frame(&mut open, Interaction::None));
+        assert!(!frame(&mut open, Interaction::None));
+    }
+}
@@ -0,0 +1,376 @@
+//! # Flex Row Layout
+//!
+//! A [FlexRow] lays out widgets in a single row, where each widget either keeps a fixed width,
+//! its natural width, or gets a share of the leftover width according to its weight.
+//! A typical use is a toolbar, where a title [Label](crate::label::Label) stretches and the
+//! [Button](crate::button::Button)s keep their natural size.
+//!
+//! ## Core Features
+//!
+//! - Fixed width ([FlexSize::Fixed]), natural width ([FlexSize::Auto]) or weighted ([FlexSize::Weight]) children
+//! - The leftover width of the [Ui] is shared between the weighted children
+//! - Without weighted children, the row as a whole is aligned according to the [Ui]'s alignment
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::button::Button;
+//! # use kolibri_embedded_gui::flex::FlexSize;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//! ui.flex_row(|row| {
+//!     row.add(Label::new("Motor Control").auto_truncate(), FlexSize::Weight(1));
+//!     if row.add(Button::new("Stop"), FlexSize::Auto).clicked() {
+//!         // ...
+//!     }
+//!     row.add(Button::new("Settings"), FlexSize::Auto);
+//!     Ok(())
+//! }).unwrap();
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! Laying out the row takes two passes over the closure: the first pass only measures the children
+//! (using [Widget::measure]), the second pass draws them. Responses returned during the first pass
+//! are empty, so checking e.g. `clicked()` in the closure works as expected.
+//!
+//! Because the closure runs twice, it must add the same children in both passes. If the children
+//! use smartstates from a [SmartstateProvider](crate::smartstate::SmartstateProvider), use
+//! [Ui::flex_row_with_smartstates], which rewinds the provider between the passes.
+//!
+//! Children with [FlexSize::Auto] whose size can't be measured are treated like [FlexSize::Weight(1)](FlexSize::Weight).
+//! Each child is drawn in its own child [Ui] covering its cell, so children narrower than their cell are
+//! placed within it according to the [Ui]'s alignment.
+
+use crate::ui::{HorizontalAlign, InternalResponse, Response, Ui, Widget};
+use core::cmp::{max, min};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::primitives::Rectangle;
+
+/// How much width a child of a [FlexRow] takes up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlexSize {
+    /// A fixed width in pixels
+    Fixed(u32),
+    /// The natural width of the widget, as reported by [Widget::measure]
+    Auto,
+    /// A share of the leftover width, proportional to the weight
+    Weight(u16),
+}
+
+/// The pass the [FlexRow] is in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pass {
+    /// Measuring the children
+    Measure,
+    /// Drawing the children, sharing `leftover` pixels between `total_weight` weights
+    Draw { leftover: u32, total_weight: u32 },
+}
+
+/// Row sharing its width between its children. Created with [Ui::flex_row].
+pub struct FlexRow<'r, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    ui: &'r mut Ui<'a, DRAW, COL>,
+    /// Area available to the row (absolute)
+    area: Rectangle,
+    pass: Pass,
+    /// Number of children
+    count: u32,
+    /// Width taken up by the children that aren't weighted
+    fixed_width: u32,
+    /// Sum of the weights of the weighted children
+    total_weight: u32,
+    /// X offset of the next cell, relative to the row
+    x: u32,
+    /// Sum of the weights of the weighted children drawn so far
+    weight_drawn: u32,
+    /// Size covered by the cells drawn so far
+    used: Size,
+}
+
+impl<'r, 'a, DRAW, COL> FlexRow<'r, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Creates a new row in the free area of the [Ui], starting with the measure pass
+    pub(crate) fn new(ui: &'r mut Ui<'a, DRAW, COL>) -> Self {
+        let area = ui.free_area();
+        FlexRow {
+            ui,
+            area,
+            pass: Pass::Measure,
+            count: 0,
+            fixed_width: 0,
+            total_weight: 0,
+            x: 0,
+            weight_drawn: 0,
+            used: Size::zero(),
+        }
+    }
+
+    /// Returns whether the row is in its measure pass, in which nothing is drawn.
+    pub fn is_measuring(&self) -> bool {
+        self.pass == Pass::Measure
+    }
+
+    /// Adds a widget with the given width to the row.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] indicating the result of adding the widget. During the measure pass, the response is empty.
+    pub fn add(&mut self, widget: impl Widget, size: FlexSize) -> Response {
+        // split into a fixed width and a weight
+        let (fixed, weight) = match size {
+            FlexSize::Fixed(width) => (width, 0),
+            FlexSize::Auto => match widget.measure(self.ui.style(), self.area.size) {
+                Some(measured) => (measured.width, 0),
+                None => (0, 1),
+            },
+            FlexSize::Weight(weight) => (0, weight as u32),
+        };
+
+        match self.pass {
+            Pass::Measure => {
+                self.fixed_width += fixed;
+                self.total_weight += weight;
+                self.count += 1;
+                Response::new(InternalResponse::empty())
+            }
+            Pass::Draw {
+                leftover,
+                total_weight,
+            } => {
+                // distribute the rounding error, so the weighted children fill the leftover width exactly
+                let before = Self::weighted_share(leftover, self.weight_drawn, total_weight);
+                self.weight_drawn += weight;
+                let share =
+                    Self::weighted_share(leftover, self.weight_drawn, total_weight) - before;
+                self.draw_cell(widget, fixed + share)
+            }
+        }
+    }
+
+    /// Switches from the measure pass to the draw pass
+    pub(crate) fn start_drawing(&mut self) {
+        let spacing = self.spacing() * self.count.saturating_sub(1);
+        let leftover = self
+            .area
+            .size
+            .width
+            .saturating_sub(self.fixed_width + spacing);
+
+        // without weighted children, align the row as a whole
+        if self.total_weight == 0 {
+            self.x = match self.ui.align().0 {
+                HorizontalAlign::Left => 0,
+                HorizontalAlign::Center => leftover / 2,
+                HorizontalAlign::Right => leftover,
+            };
+        }
+
+        self.pass = Pass::Draw {
+            leftover,
+            total_weight: max(self.total_weight, 1),
+        };
+    }
+
+    /// Returns the part of `leftover` that `weight` out of `total_weight` get
+    /// (computed in `u64`, as large weights would overflow `u32`)
+    fn weighted_share(leftover: u32, weight: u32, total_weight: u32) -> u32 {
+        (leftover as u64 * weight as u64 / total_weight as u64) as u32
+    }
+
+    /// Returns the size covered by the cells drawn so far, measured from the start of the row
+    pub(crate) fn used_size(&self) -> Size {
+        self.used
+    }
+
+    /// Draws the widget in a cell of the given width at the current position
+    fn draw_cell(&mut self, widget: impl Widget, width: u32) -> Response {
+        let cell = self.ui.mirrored(
+            self.area,
+            Rectangle::new(
+                self.area.top_left + Point::new(self.x as i32, 0),
+                Size::new(
+                    min(width, self.area.size.width.saturating_sub(self.x)),
+                    self.area.size.height,
+                ),
+            ),
+        );
+
+        let (res, used) = self.ui.area_ui(cell, |ui| ui.add_raw(widget));
+
+        self.used = Size::new(
+            max(self.used.width, self.x + used.width),
+            max(self.used.height, used.height),
+        );
+        self.x += width + self.spacing();
+        res.unwrap_or_else(Response::from_error)
+    }
+
+    /// Horizontal space between children
+    fn spacing(&self) -> u32 {
+        self.ui.style().spacing.item_spacing.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use crate::ui::{Align, LayoutDirection, VerticalAlign};
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_flex_row_weights() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        // 58x58 usable area, 8px horizontal item spacing
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let mut areas = [Rectangle::zero(); 3];
+        ui.flex_row(|row| {
+            areas[0] = row
+                .add(Spacer::new(Size::new(1, 5)), FlexSize::Fixed(10))
+                .internal
+                .area;
+            areas[1] = row
+                .add(Spacer::new(Size::new(1, 5)), FlexSize::Weight(1))
+                .internal
+                .area;
+            areas[2] = row
+                .add(Spacer::new(Size::new(1, 5)), FlexSize::Weight(2))
+                .internal
+                .area;
+            Ok(())
+        })
+        .unwrap();
+
+        // 58 - 10 - 2 * 8 = 32px leftover, shared 1:2
+        assert_eq!(areas[0].top_left, Point::new(3, 3));
+        assert_eq!(areas[1].top_left, Point::new(21, 3));
+        assert_eq!(areas[2].top_left, Point::new(39, 3));
+    }
+
+    #[test]
+    fn test_weighted_share_large_values() {
+        type Row<'g, 'a> = FlexRow<'g, 'a, MockDisplay<Rgb565>, Rgb565>;
+        assert_eq!(Row::weighted_share(32, 1, 3), 10);
+        // would overflow u32
+        assert_eq!(Row::weighted_share(100_000, 100_000, 200_000), 50_000);
+    }
+
+    #[test]
+    fn test_flex_row_auto() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let mut areas = [Rectangle::zero(); 2];
+        ui.flex_row(|row| {
+            areas[0] = row
+                .add(Spacer::new(Size::new(1, 5)), FlexSize::Weight(1))
+                .internal
+                .area;
+            areas[1] = row.add(Button::new("A"), FlexSize::Auto).internal.area;
+            Ok(())
+        })
+        .unwrap();
+
+        // the button keeps its natural width and ends at the right edge
+        let button_width = Button::new("A")
+            .measure(&medsize_rgb565_style(), Size::new(58, 58))
+            .unwrap()
+            .width;
+        assert_eq!(areas[1].size.width, button_width);
+        assert_eq!(areas[1].top_left.x, 61 - button_width as i32);
+    }
+
+    #[test]
+    fn test_flex_row_aligned_without_weights() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_align(Align(HorizontalAlign::Right, VerticalAlign::Top));
+
+        let mut area = Rectangle::zero();
+        ui.flex_row(|row| {
+            area = row
+                .add(Spacer::new(Size::new(10, 5)), FlexSize::Fixed(10))
+                .internal
+                .area;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(area.top_left, Point::new(51, 3));
+
+        // the next widget goes below the row
+        let next = ui.add(Spacer::new(Size::new(10, 5))).internal.area;
+        assert!(next.top_left.y >= 8);
+    }
+
+    #[test]
+    fn test_flex_row_centered_as_a_whole() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_align(Align(HorizontalAlign::Center, VerticalAlign::Top));
+
+        let mut areas = [Rectangle::zero(); 2];
+        ui.flex_row(|row| {
+            for area in areas.iter_mut() {
+                *area = row
+                    .add(Spacer::new(Size::new(10, 5)), FlexSize::Fixed(10))
+                    .internal
+                    .area;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        // 28px (including the spacing) centered in the 58px usable width
+        assert_eq!(areas[0].top_left, Point::new(18, 3));
+        assert_eq!(areas[1].top_left, Point::new(36, 3));
+    }
+
+    #[test]
+    fn test_flex_row_right_to_left() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_layout_direction(LayoutDirection::RightToLeft);
+
+        let mut areas = [Rectangle::zero(); 2];
+        ui.flex_row(|row| {
+            areas[0] = row
+                .add(Spacer::new(Size::new(10, 5)), FlexSize::Fixed(10))
+                .internal
+                .area;
+            areas[1] = row
+                .add(Spacer::new(Size::new(10, 5)), FlexSize::Fixed(10))
+                .internal
+                .area;
+            Ok(())
+        })
+        .unwrap();
+
+        // the first cell is at the right edge, the second one left of it
+        assert_eq!(areas[0].top_left, Point::new(51, 3));
+        assert_eq!(areas[1].top_left, Point::new(33, 3));
+    }
+}
@@ -0,0 +1,379 @@
+//! # Gesture Recognizer
+//!
+//! The [GestureRecognizer] turns timestamped touch samples (e.g. straight from a touch screen driver) into
+//! [Interaction]s, recognizing long presses, double taps and swipes on top of the plain pointer interactions.
+//!
+//! ## Core Features
+//!
+//! - Emits [Interaction::Click], [Interaction::Drag] and [Interaction::Release] for the raw touch state
+//! - [Interaction::LongPress] when a touch is held in place, reported by [Response::long_pressed](crate::ui::Response::long_pressed)
+//! - [Interaction::DoubleTap] for a second tap shortly after the first one, reported by [Response::double_clicked](crate::ui::Response::double_clicked)
+//! - [Interaction::Swipe] with the [Direction] and velocity of a fast movement
+//! - Configurable timings and distances
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::*;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::button::Button;
+//! # use kolibri_embedded_gui::gesture::GestureRecognizer;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! # fn millis() -> u32 { 0 }
+//! # fn read_touch() -> Option<Point> { None }
+//! let mut gestures = GestureRecognizer::new().long_press_time(800);
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!
+//!     let interaction = gestures.update(millis(), read_touch());
+//!     if let Interaction::Swipe(Direction::Left, _) = interaction {
+//!         // next page
+//!     }
+//!     ui.interact(interaction);
+//!
+//!     let response = ui.add(Button::new("Delete"));
+//!     if response.long_pressed() {
+//!         // secondary action
+//!     }
+//!     # break;
+//! }
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! The recognizer is fed one sample per frame: the time in milliseconds (from any monotonic clock,
+//! wrapping around is fine) and the touched point, or `None` if the screen isn't touched.
+//!
+//! - A touch that stays within the tap distance for the long press time emits a single [Interaction::LongPress].
+//!   Its release then emits [Interaction::None], so the long press doesn't click the widget as well.
+//! - A release that moved at least the swipe distance, at least as fast as the swipe velocity, emits an
+//!   [Interaction::Swipe] instead of an [Interaction::Release].
+//! - A tap (a release that didn't move further than the tap distance) within the double tap time of the previous
+//!   tap, close to it, emits an [Interaction::DoubleTap] instead of an [Interaction::Release].
+//!
+//! As swipes, long presses and double taps are only recognized from multiple samples, the interactions emitted
+//! before (e.g. the [Interaction::Click] at the start of a long press) are still passed on as usual.
+
+use crate::ui::{Direction, Interaction};
+use embedded_graphics::geometry::Point;
+
+/// Default time (in ms) a touch has to be held for a long press
+pub const DEFAULT_LONG_PRESS_TIME: u32 = 500;
+/// Default maximum time (in ms) between two taps of a double tap
+pub const DEFAULT_DOUBLE_TAP_TIME: u32 = 300;
+/// Default maximum distance (in pixels) a tap may move
+pub const DEFAULT_TAP_DISTANCE: u32 = 10;
+/// Default minimum distance (in pixels) of a swipe
+pub const DEFAULT_SWIPE_DISTANCE: u32 = 30;
+/// Default minimum velocity (in pixels per second) of a swipe
+pub const DEFAULT_SWIPE_VELOCITY: u32 = 200;
+
+/// State of the current touch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Touch {
+    /// Time and point the touch started at
+    start_time: u32,
+    start: Point,
+    /// Last touched point
+    last: Point,
+    /// Whether the touch moved further than the tap distance
+    moved: bool,
+    /// Whether the long press was emitted already
+    long_pressed: bool,
+}
+
+/// Recognizes gestures from timestamped touch samples, see the [module docs](crate::gesture).
+#[derive(Clone, Debug)]
+pub struct GestureRecognizer {
+    long_press_time: u32,
+    double_tap_time: u32,
+    tap_distance: u32,
+    swipe_distance: u32,
+    swipe_velocity: u32,
+    touch: Option<Touch>,
+    /// Time and point of the last tap, for recognizing double taps
+    last_tap: Option<(u32, Point)>,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureRecognizer {
+    /// Creates a new gesture recognizer with the default timings and distances.
+    pub fn new() -> Self {
+        GestureRecognizer {
+            long_press_time: DEFAULT_LONG_PRESS_TIME,
+            double_tap_time: DEFAULT_DOUBLE_TAP_TIME,
+            tap_distance: DEFAULT_TAP_DISTANCE,
+            swipe_distance: DEFAULT_SWIPE_DISTANCE,
+            swipe_velocity: DEFAULT_SWIPE_VELOCITY,
+            touch: None,
+            last_tap: None,
+        }
+    }
+
+    /// Sets the time (in ms) a touch has to be held for a long press.
+    pub fn long_press_time(mut self, ms: u32) -> Self {
+        self.long_press_time = ms;
+        self
+    }
+
+    /// Sets the maximum time (in ms) between two taps of a double tap.
+    pub fn double_tap_time(mut self, ms: u32) -> Self {
+        self.double_tap_time = ms;
+        self
+    }
+
+    /// Sets the maximum distance (in pixels) a touch may move to still count as a tap or long press.
+    pub fn tap_distance(mut self, distance: u32) -> Self {
+        self.tap_distance = distance;
+        self
+    }
+
+    /// Sets the minimum distance (in pixels) and velocity (in pixels per second) of a swipe.
+    pub fn swipe(mut self, distance: u32, velocity: u32) -> Self {
+        self.swipe_distance = distance;
+        self.swipe_velocity = velocity;
+        self
+    }
+
+    /// Feeds a touch sample to the recognizer.
+    ///
+    /// `time` is in milliseconds, `touch` is the touched point, or `None` if the screen isn't touched.
+    ///
+    /// ## Returns
+    ///
+    /// The [Interaction] to pass to [Ui::interact](crate::ui::Ui::interact).
+    pub fn update(&mut self, time: u32, touch: Option<Point>) -> Interaction {
+        match (self.touch.as_mut(), touch) {
+            (None, Some(point)) => {
+                self.touch = Some(Touch {
+                    start_time: time,
+                    start: point,
+                    last: point,
+                    moved: false,
+                    long_pressed: false,
+                });
+                Interaction::Click(point)
+            }
+            (Some(current), Some(point)) => {
+                current.last = point;
+                current.moved |= distance(current.start, point) > self.tap_distance;
+                let held = time.wrapping_sub(current.start_time);
+                if !current.moved && !current.long_pressed && held >= self.long_press_time {
+                    current.long_pressed = true;
+                    Interaction::LongPress(point)
+                } else {
+                    Interaction::Drag(point)
+                }
+            }
+            (Some(_), None) => self.release(time),
+            (None, None) => Interaction::None,
+        }
+    }
+
+    /// Ends the current touch, returning the interaction for its release
+    fn release(&mut self, time: u32) -> Interaction {
+        let Some(touch) = self.touch.take() else {
+            return Interaction::None;
+        };
+        let duration = time.wrapping_sub(touch.start_time);
+
+        if touch.long_pressed {
+            self.last_tap = None;
+            return Interaction::None;
+        }
+
+        if touch.moved {
+            self.last_tap = None;
+            let travelled = distance(touch.start, touch.last);
+            let velocity = (travelled as u64 * 1000 / duration.max(1) as u64) as u32;
+            if travelled >= self.swipe_distance && velocity >= self.swipe_velocity {
+                let direction = Direction::from_offset(touch.last - touch.start);
+                return Interaction::Swipe(direction, velocity);
+            }
+            return Interaction::Release(touch.last);
+        }
+
+        // a tap, maybe the second one of a double tap
+        let double_tap = self.last_tap.is_some_and(|(tap_time, tap)| {
+            time.wrapping_sub(tap_time) <= self.double_tap_time
+                && distance(tap, touch.last) <= self.tap_distance
+        });
+        if double_tap {
+            self.last_tap = None;
+            Interaction::DoubleTap(touch.last)
+        } else {
+            self.last_tap = Some((time, touch.last));
+            Interaction::Release(touch.last)
+        }
+    }
+}
+
+/// Returns the (rounded down) distance between two points
+fn distance(a: Point, b: Point) -> u32 {
+    let d = a - b;
+    let squared = (d.x.unsigned_abs() as u64).pow(2) + (d.y.unsigned_abs() as u64).pow(2);
+    // integer square root (Newton's method), as u64::isqrt needs a newer Rust version
+    if squared < 2 {
+        return squared as u32;
+    }
+    let mut root = squared;
+    let mut next = (root + squared / root) / 2;
+    while next < root {
+        root = next;
+        next = (root + squared / root) / 2;
+    }
+    root as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds the samples to a new recognizer, returning the emitted interactions
+    fn run<const N: usize>(samples: [(u32, Option<Point>); N]) -> [Interaction; N] {
+        let mut gestures = GestureRecognizer::new();
+        samples.map(|(time, touch)| gestures.update(time, touch))
+    }
+
+    #[test]
+    fn test_tap() {
+        let p = Point::new(20, 20);
+        assert_eq!(
+            run([(0, Some(p)), (50, Some(p)), (100, None), (150, None)]),
+            [
+                Interaction::Click(p),
+                Interaction::Drag(p),
+                Interaction::Release(p),
+                Interaction::None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_press() {
+        let p = Point::new(20, 20);
+        // slight jitter is fine
+        let q = Point::new(23, 19);
+        assert_eq!(
+            run([
+                (0, Some(p)),
+                (499, Some(q)),
+                (500, Some(q)),
+                (800, Some(q)),
+                (900, None)
+            ]),
+            [
+                Interaction::Click(p),
+                Interaction::Drag(q),
+                Interaction::LongPress(q),
+                Interaction::Drag(q),
+                Interaction::None
+            ]
+        );
+
+        // moving too far isn't a long press
+        let far = Point::new(40, 20);
+        assert_eq!(
+            run([(0, Some(p)), (600, Some(far))]),
+            [Interaction::Click(p), Interaction::Drag(far)]
+        );
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let p = Point::new(20, 20);
+        let q = Point::new(22, 22);
+        assert_eq!(
+            run([(0, Some(p)), (50, None), (200, Some(q)), (250, None)]),
+            [
+                Interaction::Click(p),
+                Interaction::Release(p),
+                Interaction::Click(q),
+                Interaction::DoubleTap(q)
+            ]
+        );
+
+        // too slow for a double tap
+        assert_eq!(
+            run([(0, Some(p)), (50, None), (400, Some(p)), (450, None)])[3],
+            Interaction::Release(p)
+        );
+    }
+
+    #[test]
+    fn test_swipe() {
+        let start = Point::new(100, 50);
+        let end = Point::new(40, 60);
+        // 60px in 100ms
+        assert_eq!(
+            run([(0, Some(start)), (100, Some(end)), (100, None)])[2],
+            Interaction::Swipe(Direction::Left, 600)
+        );
+
+        // too slow, so it's a normal drag
+        assert_eq!(
+            run([(0, Some(start)), (1000, Some(end)), (1000, None)])[2],
+            Interaction::Release(end)
+        );
+    }
+
+    #[test]
+    fn test_wrapping_time() {
+        let p = Point::new(20, 20);
+        let start = u32::MAX - 100;
+        assert_eq!(
+            run([(start, Some(p)), (start.wrapping_add(600), Some(p))])[1],
+            Interaction::LongPress(p)
+        );
+    }
+
+    #[test]
+    fn test_gestures_in_responses() {
+        use crate::button::Button;
+        use crate::style::medsize_rgb565_style;
+        use crate::ui::{Rotation, Ui};
+        use embedded_graphics::mock_display::MockDisplay;
+        use embedded_graphics::pixelcolor::Rgb565;
+
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::LongPress(Point::new(10, 10)));
+        let response = ui.add(Button::new("A"));
+        assert!(response.long_pressed());
+        assert!(!response.clicked());
+
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::DoubleTap(Point::new(10, 10)));
+        assert!(ui.add(Button::new("A")).double_clicked());
+
+        // swipes are rotated along with the ui
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui =
+            Ui::new_fullscreen_rotated(&mut display, medsize_rgb565_style(), Rotation::Deg90);
+        ui.interact(Interaction::Swipe(Direction::Left, 300));
+        assert_eq!(ui.interaction(), Interaction::Swipe(Direction::Down, 300));
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance(Point::new(0, 0), Point::new(3, 4)), 5);
+        assert_eq!(distance(Point::new(10, 10), Point::new(10, 10)), 0);
+        assert_eq!(distance(Point::new(0, 0), Point::new(-60, 10)), 60);
+        // rounded down
+        assert_eq!(distance(Point::new(1, 1), Point::zero()), 1);
+        assert_eq!(distance(Point::new(10, 10), Point::zero()), 14);
+    }
+}
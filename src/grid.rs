@@ -0,0 +1,295 @@
+//! # Grid Layout
+//!
+//! A [Grid] places widgets in a table with fixed column widths, so that label / value / control
+//! rows line up without having to align them by hand (e.g. with [Spacer::new_with_right_bound](crate::spacer::Spacer::new_with_right_bound)).
+//!
+//! ## Core Features
+//!
+//! - Column widths in fixed pixels or as fractions of the grid's width
+//! - Each added widget goes into the next cell, wrapping to the next row after the last column
+//! - Each row is as tall as its tallest cell
+//! - Cells can hold multiple widgets using [Grid::cell]
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::button::Button;
+//! # use kolibri_embedded_gui::grid::GridColumn;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//! let columns = [
+//!     GridColumn::Fixed(80),       // label column
+//!     GridColumn::Fraction(1, 3),  // value column
+//!     GridColumn::Fraction(1, 3),  // control column
+//! ];
+//!
+//! ui.grid(&columns, |grid| {
+//!     grid.add(Label::new("Speed"));
+//!     grid.add(Label::new("120 rpm"));
+//!     if grid.add(Button::new("Reset")).clicked() {
+//!         // ...
+//!     }
+//!
+//!     grid.add(Label::new("Temp"));
+//!     grid.add(Label::new("42 C"));
+//!     grid.end_row();
+//!     Ok(())
+//! }).unwrap();
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! Every cell is drawn in its own child [Ui] that covers the cell, so widgets inside a cell are
+//! placed and aligned the same way as in any other [Ui]. Columns are separated by the horizontal
+//! item spacing, which is taken from the width of the column left of it (so fractions adding up to `1`
+//! fill the [Ui] exactly). Rows are separated by the vertical item spacing.
+//!
+//! As widgets are drawn immediately, the height of a row is only known once all of its cells are drawn.
+//! Cells drawn earlier in the row keep their own height.
+
+use crate::ui::{GuiResult, Response, Ui, Widget};
+use core::cmp::{max, min};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::primitives::Rectangle;
+
+/// Width of a [Grid] column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridColumn {
+    /// A fixed width in pixels
+    Fixed(u32),
+    /// A fraction (`numerator / denominator`) of the width of the grid (the free width when it was created)
+    Fraction(u32, u32),
+}
+
+impl GridColumn {
+    /// Returns the width of the column in pixels, given the width of the grid
+    pub fn width(&self, grid_width: u32) -> u32 {
+        match *self {
+            GridColumn::Fixed(width) => width,
+            GridColumn::Fraction(_, 0) => 0,
+            GridColumn::Fraction(numerator, denominator) => {
+                // in u64, as large fractions (e.g. `1_000_000 / 3_000_000`) would overflow u32
+                (grid_width as u64 * numerator as u64 / denominator as u64) as u32
+            }
+        }
+    }
+}
+
+/// Table layout placing each widget in the next cell. Created with [Ui::grid].
+pub struct Grid<'g, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    ui: &'g mut Ui<'a, DRAW, COL>,
+    columns: &'g [GridColumn],
+    /// Area available to the grid (absolute)
+    area: Rectangle,
+    /// Column of the next cell
+    col: usize,
+    /// X offset of the next cell, relative to the grid
+    cell_x: u32,
+    /// Y offset of the current row, relative to the grid
+    row_y: u32,
+    /// Height of the tallest cell in the current row
+    row_height: u32,
+    /// Size covered by the cells drawn so far
+    used: Size,
+}
+
+impl<'g, 'a, DRAW, COL> Grid<'g, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Creates a new grid in the free area of the [Ui]
+    pub(crate) fn new(ui: &'g mut Ui<'a, DRAW, COL>, columns: &'g [GridColumn]) -> Self {
+        let area = ui.free_area();
+        Grid {
+            ui,
+            columns,
+            area,
+            col: 0,
+            cell_x: 0,
+            row_y: 0,
+            row_height: 0,
+            used: Size::zero(),
+        }
+    }
+
+    /// Adds a widget in the next cell of the grid.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] indicating the result of adding the widget.
+    pub fn add(&mut self, widget: impl Widget) -> Response {
+        self.cell(|ui| ui.add_raw(widget))
+            .unwrap_or_else(Response::from_error)
+    }
+
+    /// Runs `f` on a [Ui] covering the next cell of the grid, e.g. to place multiple widgets in one cell.
+    ///
+    /// ## Returns
+    ///
+    /// The result of `f`.
+    pub fn cell<F, R>(&mut self, f: F) -> GuiResult<R>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<R>,
+    {
+        let column_width = self.column_width();
+        let cell = self.ui.mirrored(
+            self.area,
+            Rectangle::new(
+                self.area.top_left + Point::new(self.cell_x as i32, self.row_y as i32),
+                Size::new(
+                    min(
+                        column_width,
+                        self.area.size.width.saturating_sub(self.cell_x),
+                    ),
+                    self.area.size.height.saturating_sub(self.row_y),
+                ),
+            ),
+        );
+
+        let (res, used) = self.ui.area_ui(cell, f);
+
+        self.row_height = max(self.row_height, used.height);
+        self.used = Size::new(
+            max(self.used.width, self.cell_x + used.width),
+            max(self.used.height, self.row_y + self.row_height),
+        );
+        self.skip();
+        res
+    }
+
+    /// Leaves the next cell empty.
+    pub fn skip(&mut self) {
+        if self.col + 1 >= self.columns.len() {
+            self.end_row();
+        } else {
+            self.cell_x += self.columns[self.col].width(self.area.size.width);
+            self.col += 1;
+        }
+    }
+
+    /// Ends the current row, so the next widget is placed in the first column of the next row.
+    ///
+    /// Does nothing if no cell of the current row has been filled or skipped yet.
+    pub fn end_row(&mut self) {
+        if self.col == 0 && self.cell_x == 0 && self.row_height == 0 {
+            return;
+        }
+        self.row_y += self.row_height + self.ui.style().spacing.item_spacing.height;
+        self.row_height = 0;
+        self.col = 0;
+        self.cell_x = 0;
+    }
+
+    /// Returns the size covered by the cells drawn so far
+    pub(crate) fn used_size(&self) -> Size {
+        self.used
+    }
+
+    /// Width available for widgets in the current column (excluding the spacing to the next column)
+    fn column_width(&self) -> u32 {
+        let width = self
+            .columns
+            .get(self.col)
+            .map(|col| col.width(self.area.size.width))
+            .unwrap_or(0);
+        if self.col + 1 < self.columns.len() {
+            width.saturating_sub(self.ui.style().spacing.item_spacing.width)
+        } else {
+            width
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_column_width() {
+        assert_eq!(GridColumn::Fixed(20).width(100), 20);
+        assert_eq!(GridColumn::Fraction(1, 4).width(100), 25);
+        assert_eq!(GridColumn::Fraction(1, 0).width(100), 0);
+        assert_eq!(GridColumn::Fraction(1_000_000, 3_000_000).width(300), 100);
+    }
+
+    #[test]
+    fn test_grid_cells() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        // 58x58 usable area, 8px horizontal and 4px vertical item spacing
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        let columns = [GridColumn::Fixed(20), GridColumn::Fraction(1, 2)];
+
+        let mut areas = [Rectangle::zero(); 4];
+        ui.grid(&columns, |grid| {
+            areas[0] = grid.add(Spacer::new(Size::new(5, 5))).internal.area;
+            areas[1] = grid.add(Spacer::new(Size::new(5, 10))).internal.area;
+            // the row is as high as its tallest cell
+            areas[2] = grid.add(Spacer::new(Size::new(5, 5))).internal.area;
+            grid.skip();
+            grid.end_row();
+            areas[3] = grid.add(Spacer::new(Size::new(5, 5))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(areas[0].top_left, Point::new(3, 3));
+        assert_eq!(areas[1].top_left, Point::new(23, 3));
+        assert_eq!(areas[2].top_left, Point::new(3, 17));
+        assert_eq!(areas[3].top_left, Point::new(3, 26));
+    }
+
+    #[test]
+    fn test_grid_fractions_of_grid_width() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        // the grid starts at x = 21, so only 40px of the 58px are left
+        ui.add_horizontal(Spacer::new(Size::new(10, 5)));
+
+        let columns = [GridColumn::Fraction(1, 2), GridColumn::Fraction(1, 2)];
+        let mut areas = [Rectangle::zero(); 2];
+        ui.grid(&columns, |grid| {
+            areas[0] = grid.add(Spacer::new(Size::new(5, 5))).internal.area;
+            areas[1] = grid.add(Spacer::new(Size::new(20, 5))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(areas[0].top_left, Point::new(21, 3));
+        assert_eq!(areas[1].top_left, Point::new(41, 3));
+        // the last column ends at the right edge of the ui
+        assert_eq!(areas[1].bottom_right(), Some(Point::new(60, 7)));
+    }
+
+    #[test]
+    fn test_grid_cell_too_small() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        ui.grid(&[GridColumn::Fixed(20), GridColumn::Fixed(20)], |grid| {
+            // the spacing to the next column is taken from the cell
+            assert!(grid.add(Spacer::new(Size::new(13, 5))).error().is_some());
+            assert!(grid.add(Spacer::new(Size::new(20, 5))).error().is_none());
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,380 @@
+//! # Group
+//!
+//! A [Group] draws a rounded frame with a title around related widgets, like a group box on the desktop.
+//! A typical use is grouping the controls of one device, e.g. the speed and direction of a motor.
+//!
+//! ## Core Features
+//!
+//! - Rounded border using the style's border color, width (at least 1px) and corner radius
+//! - Title inset into the top edge of the border
+//! - Sizes itself to its content, so there's no need to guess its height
+//! - Smartstate support: the frame is only redrawn when its position or size changes
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::slider::Slider;
+//! # use kolibri_embedded_gui::group::Group;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//! let mut speed = 50;
+//! Group::new("Motor")
+//!     .show(&mut ui, |ui| {
+//!         ui.add(Label::new("Speed"));
+//!         ui.add(Slider::new(&mut speed, 0..=100));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! The content is added first, in a child [Ui] covering the free area of the [Ui] (minus the frame).
+//! Afterwards, the space taken up by the content and the frame is allocated, and the frame is drawn around it.
+//! Like [Ui::add], a new row is started after the group.
+//!
+//! With [Group::show_with_smartstates], the group uses one smartstate for its frame, before the smartstates of its content.
+//! When the frame moves or is resized, the old frame is cleared along with everything after the group (which is then
+//! forced to redraw). If the group moves down, the widgets above it are expected to have redrawn the space it left.
+
+use crate::smartstate::{Smartstate, SmartstateProvider};
+use crate::ui::{GuiError, GuiResult, InternalResponse, Response, Ui};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle, RoundedRectangle};
+use embedded_graphics::text::{Baseline, Text};
+
+/// Horizontal gap between the title and the border
+const TITLE_GAP: u32 = 2;
+
+/// Framed container with a title. See the [module docs](crate::group) for details.
+pub struct Group<'a> {
+    title: &'a str,
+}
+
+impl<'a> Group<'a> {
+    /// Creates a new group with the given title.
+    pub fn new(title: &'a str) -> Self {
+        Group { title }
+    }
+
+    /// Shows the group, with `f` adding its content.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the whole group, including the frame.
+    pub fn show<DRAW, COL, F>(self, ui: &mut Ui<DRAW, COL>, f: F) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.show_inner(ui, None, f)
+    }
+
+    /// Shows the group, with `f` adding its content using the given smartstates.
+    ///
+    /// The frame is only redrawn when its position or size changed (or its smartstate was forced to redraw).
+    /// If it moved or was resized, the old frame and everything after the group are cleared, and the smartstates
+    /// after the group are forced to redraw.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the whole group, including the frame.
+    pub fn show_with_smartstates<DRAW, COL, F, const N: usize>(
+        self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: &mut SmartstateProvider<N>,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        let mut frame_smartstate = *smartstates.nxt();
+        let frame_pos = smartstates.get_pos() - 1;
+        let drawn = !frame_smartstate.is_empty();
+
+        let res = self.show_inner(ui, Some(&mut frame_smartstate), |ui| f(ui, smartstates));
+
+        *smartstates.get(frame_pos) = frame_smartstate;
+        // the frame moved, so everything after the group was cleared
+        if drawn && res.as_ref().is_ok_and(|res| res.redrawn()) {
+            smartstates.force_redraw_remaining();
+        }
+        res
+    }
+
+    fn show_inner<DRAW, COL, F>(
+        &self,
+        ui: &mut Ui<DRAW, COL>,
+        frame_smartstate: Option<&mut Smartstate>,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let style = *ui.style();
+        let font = style.default_font;
+        let title_height = font.character_size.height;
+        // styles without borders would leave the group invisible
+        let border = max(style.border_width, 1);
+        let padding = style.spacing.window_border_padding;
+
+        // space between the edges of the group and its content
+        let inset_start = Size::new(border + padding.width, title_height + padding.height);
+        let inset_end = Size::new(border + padding.width, border + padding.height);
+
+        let free = ui.free_area();
+        let content_bounds = Rectangle::new(
+            free.top_left + inset_start,
+            free.size.saturating_sub(inset_start + inset_end),
+        );
+        let (res, used) = ui.area_ui(content_bounds, f);
+        res?;
+
+        let mut title = Text::new(
+            self.title,
+            Point::zero(),
+            MonoTextStyle::new(&font, style.text_color),
+        );
+        title.text_style.baseline = Baseline::Top;
+        let title_width = title.bounding_box().size.width;
+        let title_inset = style.corner_radius + padding.width;
+
+        let size = Size::new(
+            max(
+                used.width + inset_start.width + inset_end.width,
+                title_width + 2 * (title_inset + TITLE_GAP),
+            ),
+            used.height + inset_start.height + inset_end.height,
+        );
+        let area = ui.allocate_at_cursor(size)?;
+        ui.new_row();
+
+        // whether the frame was drawn somewhere else (or with another size) before
+        let mut moved = false;
+        let redraw = match frame_smartstate {
+            Some(smartstate) => {
+                let redraw = !smartstate.is_state_hashed(&area);
+                moved = redraw && !smartstate.is_empty();
+                smartstate.set_state_hashed(&area);
+                redraw
+            }
+            None => true,
+        };
+
+        if moved {
+            // clear the old frame, which is somewhere in the free area the group started in
+            // (and everything after the group, which is forced to redraw by show_with_smartstates)
+            let beside = ui.mirrored(
+                free,
+                Rectangle::new(
+                    free.top_left + Point::new(area.size.width as i32, 0),
+                    free.size.saturating_sub(Size::new(area.size.width, 0)),
+                ),
+            );
+            let under = ui.mirrored(
+                free,
+                Rectangle::new(
+                    free.top_left + Point::new(0, area.size.height as i32),
+                    Size::new(
+                        area.size.width,
+                        free.size.height.saturating_sub(area.size.height),
+                    ),
+                ),
+            );
+            ui.clear_area(beside)?;
+            ui.clear_area(under)?;
+            ui.clear_area(ui.free_area())?;
+
+            // and the inset around the content, where the new frame goes
+            let width = area.size.width;
+            let height = area.size.height;
+            let ring = [
+                Rectangle::new(area.top_left, Size::new(width, inset_start.height)),
+                Rectangle::new(
+                    area.top_left + Point::new(0, (height - inset_end.height) as i32),
+                    Size::new(width, inset_end.height),
+                ),
+                Rectangle::new(area.top_left, Size::new(inset_start.width, height)),
+                Rectangle::new(
+                    area.top_left + Point::new((width - inset_end.width) as i32, 0),
+                    Size::new(inset_end.width, height),
+                ),
+            ];
+            for strip in ring {
+                ui.clear_area(strip)?;
+            }
+        }
+
+        if redraw {
+            // the border runs through the middle of the title
+            let frame = Rectangle::new(
+                area.top_left + Point::new(0, (title_height / 2) as i32),
+                area.size.saturating_sub(Size::new(0, title_height / 2)),
+            );
+            let frame_style = PrimitiveStyleBuilder::new()
+                .stroke_color(style.border_color)
+                .stroke_width(border)
+                .build();
+            ui.draw(
+                &RoundedRectangle::with_equal_corners(
+                    frame,
+                    Size::new(style.corner_radius, style.corner_radius),
+                )
+                .into_styled(frame_style),
+            )
+            .map_err(|_| GuiError::DrawError(Some("Couldn't draw group frame")))?;
+
+            // cut the title into the border (at the start of the top edge)
+            let title_area = ui.mirrored(
+                area,
+                Rectangle::new(
+                    area.top_left + Point::new(title_inset as i32, 0),
+                    Size::new(title_width + 2 * TITLE_GAP, title_height),
+                ),
+            );
+            title.translate_mut(title_area.top_left + Point::new(TITLE_GAP as i32, 0));
+            ui.clear_area(title_area)?;
+            ui.draw(&title)
+                .map_err(|_| GuiError::DrawError(Some("Couldn't draw group title")))?;
+        }
+
+        Ok(Response::new(InternalResponse::new(area, ui.check_interact(area))).set_redraw(redraw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_group_sizes_to_content() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let mut content = Rectangle::zero();
+        let response = Group::new("M")
+            .show(&mut ui, |ui| {
+                content = ui.add(Spacer::new(Size::new(30, 5))).internal.area;
+                Ok(())
+            })
+            .unwrap();
+
+        // 15px title, 1px border, 3px padding
+        assert_eq!(content.top_left, Point::new(7, 21));
+        assert_eq!(
+            response.internal.area,
+            Rectangle::new(Point::new(3, 3), Size::new(38, 27))
+        );
+
+        // the next widget goes below the group
+        let next = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+        assert_eq!(next.top_left, Point::new(3, 34));
+    }
+
+    #[test]
+    fn test_group_at_least_as_wide_as_title() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let response = Group::new("M")
+            .show(&mut ui, |ui| {
+                ui.add(Spacer::new(Size::new(1, 5)));
+                Ok(())
+            })
+            .unwrap();
+
+        // 9px title, inset by the corner radius, padding and gap on both sides
+        assert_eq!(response.internal.area.size.width, 9 + 2 * (8 + 3 + 2));
+    }
+
+    #[test]
+    fn test_group_redraws_frame_on_resize() {
+        let mut smartstates = SmartstateProvider::<4>::new();
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let style = medsize_rgb565_style();
+
+        // shows the group, returning whether it was redrawn, and whether the smartstate after it was forced to redraw
+        let mut frame = |display: &mut MockDisplay<Rgb565>, offset: u32, content_height: u32| {
+            let mut ui = Ui::new_fullscreen(display, style);
+            smartstates.restart_counter();
+            ui.add(Spacer::new(Size::new(1, offset)));
+
+            let response = Group::new("M")
+                .show_with_smartstates(&mut ui, &mut smartstates, |ui, _| {
+                    ui.add(Spacer::new(Size::new(30, content_height)));
+                    Ok(())
+                })
+                .unwrap();
+            let redrawn = response.redrawn();
+            let below = smartstates.nxt();
+            let below_forced = below.is_empty();
+            *below = Smartstate::state(1);
+            (redrawn, below_forced)
+        };
+
+        // 1px spacer and 4px spacing above, 15px title, 1px border, 3px padding
+        assert_eq!(frame(&mut display, 1, 10), (true, true));
+        assert_eq!(
+            display.get_pixel(Point::new(20, 39)),
+            Some(style.border_color)
+        );
+        assert_eq!(frame(&mut display, 1, 10), (false, false));
+
+        // shrinking clears the old bottom border, and redraws the widgets below
+        assert_eq!(frame(&mut display, 1, 5), (true, true));
+        assert_eq!(
+            display.get_pixel(Point::new(20, 39)),
+            Some(style.background_color)
+        );
+        assert_eq!(
+            display.get_pixel(Point::new(20, 34)),
+            Some(style.border_color)
+        );
+        assert_eq!(frame(&mut display, 1, 5), (false, false));
+
+        // moving up clears the old frame as well
+        assert_eq!(frame(&mut display, 0, 5), (true, true));
+        assert_eq!(
+            display.get_pixel(Point::new(20, 34)),
+            Some(style.background_color)
+        );
+        assert_eq!(
+            display.get_pixel(Point::new(20, 33)),
+            Some(style.border_color)
+        );
+        assert_eq!(
+            display.get_pixel(Point::new(35, 15)),
+            Some(style.background_color)
+        );
+        assert_eq!(
+            display.get_pixel(Point::new(35, 14)),
+            Some(style.border_color)
+        );
+        assert_eq!(frame(&mut display, 0, 5), (false, false));
+    }
+}
@@ -0,0 +1,612 @@
+//! # Touch Calibration and Filtering
+//!
+//! Helpers for resistive touch controllers (e.g. the XPT2046), which report noisy raw ADC values
+//! instead of screen coordinates.
+//!
+//! ## Core Features
+//!
+//! - [TouchCalibration]: 3-point affine calibration, mapping raw values to screen coordinates
+//!   (handling scaling, offsets, swapped / mirrored axes and slight rotation)
+//! - [CalibrationScreen]: draws crosshair targets with a [Ui] and collects the samples for a [TouchCalibration]
+//! - [TouchFilter]: median / averaging filter with a pressure threshold and release debouncing,
+//!   turning raw [TouchSample]s into clean [Interaction]s
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::*;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::helpers::calibration::*;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! # fn read_touch() -> Option<TouchSample> { None }
+//! // calibrate (or load a stored calibration with TouchCalibration::from_coefficients)
+//! let mut screen = CalibrationScreen::new(display.bounding_box());
+//! let mut raw_filter = TouchFilter::<5>::new(TouchCalibration::identity()).pressure_threshold(100);
+//! let calibration = loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     let raw = raw_filter.update(read_touch());
+//!     if let Some(calibration) = screen.update(&mut ui, raw).unwrap() {
+//!         break calibration;
+//!     }
+//! };
+//!
+//! let mut touch = TouchFilter::<5>::new(calibration).pressure_threshold(100);
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     ui.interact(touch.update(read_touch()));
+//!     ui.add(Label::new("Calibrated!"));
+//!     # break;
+//! }
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! The calibration uses integer math only: the affine transformation is stored as six coefficients
+//! and a common divisor, which can be stored (e.g. in flash) with [TouchCalibration::coefficients].
+//!
+//! The [TouchFilter] keeps a window of the last `N` samples. For each axis, the `trim` lowest and highest
+//! values are dropped, and the rest is averaged. With a trim of `(N - 1) / 2`, this is a median filter;
+//! with a trim of 0, a plain average. A touch is only reported once the window is full, so the first
+//! (usually unreliable) samples of a press settle first.
+//!
+//! The [CalibrationScreen] is fed the [Interaction]s of a [TouchFilter] with an identity calibration, so it gets
+//! filtered raw values. The raw values of each press are averaged, and recorded for the current target on release.
+//! The [Ui] should not be rotated, as the targets are in the coordinates of the drawable.
+
+use crate::ui::{GuiError, GuiResult, Interaction, Ui};
+use core::cmp::min;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Line, PrimitiveStyle, Rectangle};
+use heapless::Deque;
+
+/// Length of the arms of a crosshair target, in pixels
+const CROSSHAIR_ARM: i32 = 8;
+
+/// Affine calibration from raw touch values to screen coordinates, computed from three points.
+///
+/// `screen.x = (a * raw.x + b * raw.y + c) / divisor`, and `screen.y = (d * raw.x + e * raw.y + f) / divisor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TouchCalibration {
+    /// `[a, b, c, d, e, f, divisor]`, with a positive divisor
+    coefficients: [i64; 7],
+}
+
+impl Default for TouchCalibration {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl TouchCalibration {
+    /// Creates a calibration that doesn't change the points, e.g. to filter raw values with a [TouchFilter].
+    pub const fn identity() -> Self {
+        TouchCalibration {
+            coefficients: [1, 0, 0, 0, 1, 0, 1],
+        }
+    }
+
+    /// Computes the calibration from the raw values measured at three screen points.
+    ///
+    /// The screen points should be far apart (e.g. near three corners of the screen) for good accuracy.
+    ///
+    /// ## Returns
+    ///
+    /// The calibration, or `None` if the raw values (or screen points) are on a line, so no calibration can be computed.
+    pub fn new(raw: [Point; 3], screen: [Point; 3]) -> Option<Self> {
+        let [r0, r1, r2] = raw.map(|p| (p.x as i64, p.y as i64));
+        let [s0, s1, s2] = screen.map(|p| (p.x as i64, p.y as i64));
+
+        let divisor = (r0.0 - r2.0) * (r1.1 - r2.1) - (r1.0 - r2.0) * (r0.1 - r2.1);
+        // coefficients for one screen axis, given the screen coordinates on it
+        let axis = |d0: i64, d1: i64, d2: i64| {
+            [
+                (d0 - d2) * (r1.1 - r2.1) - (d1 - d2) * (r0.1 - r2.1),
+                (r0.0 - r2.0) * (d1 - d2) - (d0 - d2) * (r1.0 - r2.0),
+                (r2.0 * d1 - r1.0 * d2) * r0.1
+                    + (r0.0 * d2 - r2.0 * d0) * r1.1
+                    + (r1.0 * d0 - r0.0 * d1) * r2.1,
+            ]
+        };
+        let [a, b, c] = axis(s0.0, s1.0, s2.0);
+        let [d, e, f] = axis(s0.1, s1.1, s2.1);
+
+        let screen_divisor = (s0.0 - s2.0) * (s1.1 - s2.1) - (s1.0 - s2.0) * (s0.1 - s2.1);
+        if divisor == 0 || screen_divisor == 0 {
+            return None;
+        }
+        let sign = divisor.signum();
+        Some(TouchCalibration {
+            coefficients: [a, b, c, d, e, f, divisor].map(|k| k * sign),
+        })
+    }
+
+    /// Creates a calibration from coefficients returned by [TouchCalibration::coefficients] (e.g. stored in flash).
+    ///
+    /// ## Returns
+    ///
+    /// The calibration, or `None` if the divisor (the last coefficient) is 0.
+    pub fn from_coefficients(coefficients: [i64; 7]) -> Option<Self> {
+        let sign = coefficients[6].signum();
+        if sign == 0 {
+            return None;
+        }
+        Some(TouchCalibration {
+            coefficients: coefficients.map(|k| k * sign),
+        })
+    }
+
+    /// Returns the coefficients `[a, b, c, d, e, f, divisor]` of the calibration, e.g. to store them.
+    pub fn coefficients(&self) -> [i64; 7] {
+        self.coefficients
+    }
+
+    /// Maps a raw touch value to screen coordinates (rounded to the nearest pixel).
+    pub fn transform(&self, raw: Point) -> Point {
+        let [a, b, c, d, e, f, divisor] = self.coefficients;
+        let (x, y) = (raw.x as i64, raw.y as i64);
+        // rounds to the nearest integer, as the divisor is positive
+        let round = |numerator: i64| (2 * numerator + divisor).div_euclid(2 * divisor) as i32;
+        Point::new(round(a * x + b * y + c), round(d * x + e * y + f))
+    }
+}
+
+/// A raw sample of the touch controller
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TouchSample {
+    /// Raw value (e.g. ADC readings) of the touched point
+    pub point: Point,
+    /// Touch pressure (higher is harder), compared to the [TouchFilter]'s pressure threshold
+    pub pressure: u32,
+}
+
+impl TouchSample {
+    /// Creates a sample with the given raw point and pressure.
+    pub const fn new(point: Point, pressure: u32) -> Self {
+        TouchSample { point, pressure }
+    }
+
+    /// Creates a sample for controllers without a pressure reading, which always passes the pressure threshold.
+    pub const fn without_pressure(point: Point) -> Self {
+        TouchSample {
+            point,
+            pressure: u32::MAX,
+        }
+    }
+}
+
+/// Filter turning raw [TouchSample]s into clean [Interaction]s, see the [module docs](crate::helpers::calibration).
+///
+/// `N` is the number of samples the filter averages over, and must be at least 1:
+///
+/// ```compile_fail
+/// # use kolibri_embedded_gui::helpers::calibration::{TouchCalibration, TouchFilter};
+/// let filter = TouchFilter::<0>::new(TouchCalibration::identity());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TouchFilter<const N: usize> {
+    calibration: TouchCalibration,
+    pressure_threshold: u32,
+    release_debounce: u32,
+    trim: usize,
+    /// Last raw samples of the current touch
+    window: Deque<Point, N>,
+    /// Current (filtered, calibrated) touch point, if touched
+    touch: Option<Point>,
+    /// Number of samples without a touch since the last one
+    released_for: u32,
+}
+
+impl<const N: usize> TouchFilter<N> {
+    /// Fails to compile for an empty window, which would have nothing to average
+    const NOT_EMPTY: () = assert!(N > 0, "TouchFilter needs a window of at least one sample");
+
+    /// Creates a filter mapping the filtered raw values with the given calibration.
+    ///
+    /// By default, there's no pressure threshold, a release is reported after two samples without a touch,
+    /// and a quarter of the samples are dropped as outliers on both sides.
+    pub fn new(calibration: TouchCalibration) -> Self {
+        let () = Self::NOT_EMPTY;
+        TouchFilter {
+            calibration,
+            pressure_threshold: 0,
+            release_debounce: 2,
+            trim: N / 4,
+            window: Deque::new(),
+            touch: None,
+            released_for: 0,
+        }
+    }
+
+    /// Sets the pressure a sample needs to count as a touch.
+    pub fn pressure_threshold(mut self, threshold: u32) -> Self {
+        self.pressure_threshold = threshold;
+        self
+    }
+
+    /// Sets the number of consecutive samples without a touch after which a release is reported.
+    ///
+    /// Shorter gaps (e.g. when a finger briefly loses pressure while dragging) are bridged.
+    pub fn release_debounce(mut self, samples: u32) -> Self {
+        self.release_debounce = samples.max(1);
+        self
+    }
+
+    /// Sets the number of lowest and highest values dropped on each axis before averaging.
+    ///
+    /// `(N - 1) / 2` (or more) makes the filter a median filter, 0 a plain average.
+    pub fn trim(mut self, trim: usize) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Returns the current (filtered, calibrated) touch point, if the screen is touched.
+    ///
+    /// This can be fed to a [GestureRecognizer](crate::gesture::GestureRecognizer).
+    pub fn touch(&self) -> Option<Point> {
+        self.touch
+    }
+
+    /// Feeds a raw sample to the filter, with `None` if the controller doesn't detect a touch.
+    ///
+    /// ## Returns
+    ///
+    /// The [Interaction] to pass to [Ui::interact]: [Interaction::Click] when a touch starts,
+    /// [Interaction::Drag] while it lasts, and [Interaction::Release] at the last point once it ends.
+    pub fn update(&mut self, sample: Option<TouchSample>) -> Interaction {
+        let sample = sample.filter(|sample| sample.pressure >= self.pressure_threshold);
+        let Some(sample) = sample else {
+            let Some(last) = self.touch else {
+                self.window.clear();
+                return Interaction::None;
+            };
+            self.released_for += 1;
+            if self.released_for < self.release_debounce {
+                return Interaction::Drag(last);
+            }
+            self.touch = None;
+            self.window.clear();
+            return Interaction::Release(last);
+        };
+
+        self.released_for = 0;
+        if self.window.is_full() {
+            self.window.pop_front();
+        }
+        self.window.push_back(sample.point).ok();
+        if !self.window.is_full() {
+            // still settling
+            return match self.touch {
+                Some(last) => Interaction::Drag(last),
+                None => Interaction::None,
+            };
+        }
+
+        let point = self.calibration.transform(self.filtered());
+        match self.touch.replace(point) {
+            Some(_) => Interaction::Drag(point),
+            None => Interaction::Click(point),
+        }
+    }
+
+    /// Averages the window on both axes, without the `trim` lowest and highest values
+    fn filtered(&self) -> Point {
+        let len = self.window.len();
+        let trim = min(self.trim, len.saturating_sub(1) / 2);
+        let axis = |coordinate: fn(&Point) -> i32| {
+            let mut values = [0; N];
+            for (value, point) in values.iter_mut().zip(self.window.iter()) {
+                *value = coordinate(point);
+            }
+            let values = &mut values[..len];
+            values.sort_unstable();
+            let kept = &values[trim..len - trim];
+            (kept.iter().map(|&v| v as i64).sum::<i64>() / kept.len() as i64) as i32
+        };
+        Point::new(axis(|p| p.x), axis(|p| p.y))
+    }
+}
+
+/// Calibration screen, drawing crosshair targets and collecting the raw values for a [TouchCalibration].
+/// See the [module docs](crate::helpers::calibration).
+#[derive(Clone, Debug)]
+pub struct CalibrationScreen {
+    targets: [Point; 3],
+    raw: [Point; 3],
+    /// Index of the current target
+    step: usize,
+    /// Sum and number of the raw values of the current press
+    sum: (i64, i64, i64),
+    /// Index of the target currently on the screen
+    drawn: Option<usize>,
+}
+
+impl CalibrationScreen {
+    /// Creates a calibration screen with targets spread over the (absolute) `area`,
+    /// near the top left, the right and the bottom.
+    pub fn new(area: Rectangle) -> Self {
+        let at = |x: u32, y: u32| {
+            area.top_left
+                + Point::new(
+                    (area.size.width * x / 10) as i32,
+                    (area.size.height * y / 10) as i32,
+                )
+        };
+        Self::with_targets([at(1, 1), at(9, 5), at(5, 9)])
+    }
+
+    /// Creates a calibration screen with the given (absolute) targets, which must not be on a line.
+    pub fn with_targets(targets: [Point; 3]) -> Self {
+        CalibrationScreen {
+            targets,
+            raw: [Point::zero(); 3],
+            step: 0,
+            sum: (0, 0, 0),
+            drawn: None,
+        }
+    }
+
+    /// Returns the targets of the calibration screen.
+    pub fn targets(&self) -> [Point; 3] {
+        self.targets
+    }
+
+    /// Returns the index of the target the user should touch next.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Starts over with the first target.
+    pub fn restart(&mut self) {
+        self.step = 0;
+        self.sum = (0, 0, 0);
+    }
+
+    /// Draws the current target, and collects the raw values from `raw`, the [Interaction] of a
+    /// [TouchFilter] with an identity calibration (see [TouchCalibration::identity]).
+    ///
+    /// Once all targets were touched, the last target is removed and the calibration is returned.
+    /// If no calibration can be computed from the collected values, the calibration starts over.
+    ///
+    /// ## Returns
+    ///
+    /// The calibration, once all targets were touched, or an error if drawing failed.
+    pub fn update<DRAW, COL>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        raw: Interaction,
+    ) -> GuiResult<Option<TouchCalibration>>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        match raw {
+            Interaction::Click(point) | Interaction::Drag(point) => self.add_sample(point),
+            Interaction::Release(point) => {
+                if self.sum.2 == 0 {
+                    self.add_sample(point);
+                }
+                let (x, y, count) = self.sum;
+                self.raw[self.step] = Point::new((x / count) as i32, (y / count) as i32);
+                self.sum = (0, 0, 0);
+                self.step += 1;
+            }
+            _ => {}
+        }
+
+        if self.drawn != Some(self.step) {
+            if let Some(drawn) = self.drawn.take() {
+                Self::draw_target(ui, self.targets[drawn], None)?;
+            }
+        }
+
+        if self.step == self.targets.len() {
+            self.restart();
+            return Ok(TouchCalibration::new(self.raw, self.targets));
+        }
+
+        if self.drawn.is_none() {
+            let color = ui.style().text_color;
+            Self::draw_target(ui, self.targets[self.step], Some(color))?;
+            self.drawn = Some(self.step);
+        }
+        Ok(None)
+    }
+
+    /// Adds a raw value to the current press
+    fn add_sample(&mut self, point: Point) {
+        self.sum.0 += point.x as i64;
+        self.sum.1 += point.y as i64;
+        self.sum.2 += 1;
+    }
+
+    /// Draws the crosshair at `target` in `color`, or removes it with `None`
+    fn draw_target<DRAW, COL>(
+        ui: &mut Ui<DRAW, COL>,
+        target: Point,
+        color: Option<COL>,
+    ) -> GuiResult<()>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let area = Rectangle::with_center(target, Size::new_equal(2 * CROSSHAIR_ARM as u32 + 1));
+        let Some(color) = color else {
+            return ui.clear_area(area);
+        };
+
+        let style = PrimitiveStyle::with_stroke(color, 1);
+        let horizontal = Line::new(
+            target - Point::new(CROSSHAIR_ARM, 0),
+            target + Point::new(CROSSHAIR_ARM, 0),
+        );
+        let vertical = Line::new(
+            target - Point::new(0, CROSSHAIR_ARM),
+            target + Point::new(0, CROSSHAIR_ARM),
+        );
+
+        ui.start_drawing(&area);
+        ui.draw(&horizontal.into_styled(style))
+            .and_then(|_| ui.draw(&vertical.into_styled(style)))
+            .map_err(|_| GuiError::DrawError(Some("Couldn't draw calibration target")))?;
+        ui.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    /// Synthetic touch controller: swapped axes, the y axis mirrored, scaled and slightly skewed
+    fn raw_of(screen: Point) -> Point {
+        Point::new(
+            300 + 60 * screen.y + screen.x,
+            3800 - 55 * screen.x + 2 * screen.y,
+        )
+    }
+
+    #[test]
+    fn test_calibration() {
+        let screen = [Point::new(6, 6), Point::new(57, 32), Point::new(32, 57)];
+        let calibration = TouchCalibration::new(screen.map(raw_of), screen).unwrap();
+
+        for point in [Point::new(0, 0), Point::new(63, 63), Point::new(20, 45)] {
+            assert_eq!(calibration.transform(raw_of(point)), point);
+        }
+
+        // a raw value between two pixels is rounded to the nearest one
+        let between = raw_of(Point::new(20, 20)) + Point::new(10, 0);
+        assert_eq!(calibration.transform(between), Point::new(20, 20));
+
+        let stored = TouchCalibration::from_coefficients(calibration.coefficients());
+        assert_eq!(stored, Some(calibration));
+    }
+
+    #[test]
+    fn test_default_targets() {
+        let screen = CalibrationScreen::new(Rectangle::new(Point::zero(), Size::new(320, 240)));
+        assert_eq!(
+            screen.targets(),
+            [
+                Point::new(32, 24),
+                Point::new(288, 120),
+                Point::new(160, 216)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calibration_needs_three_separate_points() {
+        let on_a_line = [Point::new(0, 0), Point::new(10, 10), Point::new(20, 20)];
+        let screen = [Point::new(6, 6), Point::new(57, 32), Point::new(32, 57)];
+        assert_eq!(TouchCalibration::new(on_a_line, screen), None);
+        assert_eq!(TouchCalibration::new(screen, on_a_line), None);
+        assert_eq!(
+            TouchCalibration::from_coefficients([1, 0, 0, 0, 1, 0, 0]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_filter_rejects_spikes() {
+        let mut filter = TouchFilter::<5>::new(TouchCalibration::identity());
+        let sample = |x, y| Some(TouchSample::without_pressure(Point::new(x, y)));
+
+        // settling until the window is full
+        for _ in 0..4 {
+            assert_eq!(filter.update(sample(100, 200)), Interaction::None);
+        }
+        assert_eq!(
+            filter.update(sample(102, 198)),
+            Interaction::Click(Point::new(100, 200))
+        );
+
+        // a single spike is dropped, the rest averaged
+        assert_eq!(
+            filter.update(sample(900, 0)),
+            Interaction::Drag(Point::new(100, 199))
+        );
+        assert_eq!(filter.touch(), Some(Point::new(100, 199)));
+    }
+
+    #[test]
+    fn test_filter_pressure_and_debounce() {
+        let mut filter = TouchFilter::<1>::new(TouchCalibration::identity())
+            .pressure_threshold(100)
+            .release_debounce(3);
+        let p = Point::new(10, 10);
+
+        // too light
+        assert_eq!(
+            filter.update(Some(TouchSample::new(p, 50))),
+            Interaction::None
+        );
+        assert_eq!(
+            filter.update(Some(TouchSample::new(p, 150))),
+            Interaction::Click(p)
+        );
+
+        // short gaps are bridged
+        assert_eq!(filter.update(None), Interaction::Drag(p));
+        assert_eq!(
+            filter.update(Some(TouchSample::new(p, 20))),
+            Interaction::Drag(p)
+        );
+        assert_eq!(
+            filter.update(Some(TouchSample::new(p, 150))),
+            Interaction::Drag(p)
+        );
+
+        for _ in 0..2 {
+            assert_eq!(filter.update(None), Interaction::Drag(p));
+        }
+        assert_eq!(filter.update(None), Interaction::Release(p));
+        assert_eq!(filter.update(None), Interaction::None);
+        assert_eq!(filter.touch(), None);
+    }
+
+    #[test]
+    fn test_calibration_screen() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let style = medsize_rgb565_style();
+        // far enough from the edges of the small mock display for the crosshairs
+        let targets = [Point::new(10, 10), Point::new(53, 32), Point::new(32, 53)];
+        let mut screen = CalibrationScreen::with_targets(targets);
+
+        let mut calibration = None;
+        for (step, target) in targets.into_iter().enumerate() {
+            let mut ui = Ui::new_fullscreen(&mut display, style);
+            assert_eq!(screen.update(&mut ui, Interaction::None), Ok(None));
+            drop(ui);
+            assert_eq!(display.get_pixel(target), Some(style.text_color));
+            assert_eq!(screen.step(), step);
+
+            // a slightly noisy press on the target
+            let mut ui = Ui::new_fullscreen(&mut display, style);
+            let raw = raw_of(target);
+            for offset in [-3, 3] {
+                let point = raw + Point::new(offset, offset);
+                assert_eq!(screen.update(&mut ui, Interaction::Drag(point)), Ok(None));
+            }
+            calibration = screen.update(&mut ui, Interaction::Release(raw)).unwrap();
+        }
+
+        // the last target is removed
+        assert_eq!(display.get_pixel(targets[2]), Some(style.background_color));
+        let calibration = calibration.unwrap();
+        let point = Point::new(40, 20);
+        assert_eq!(calibration.transform(raw_of(point)), point);
+    }
+}
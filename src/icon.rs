@@ -51,11 +51,12 @@
 //!
 
 use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
 use crate::ui::{GuiError, GuiResult, Response, Ui, Widget};
 use core::marker::PhantomData;
 use core::ops::Add;
 use embedded_graphics::draw_target::DrawTarget;
-use embedded_graphics::geometry::Point;
+use embedded_graphics::geometry::{Point, Size};
 use embedded_graphics::image::Image;
 use embedded_graphics::pixelcolor::PixelColor;
 use embedded_graphics::prelude::*;
@@ -155,4 +156,8 @@ impl<Ico: IconoirIcon> Widget for IconWidget<'_, Ico> {
 
         Ok(Response::new(iresponse))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        Some(Ico::new(style.icon_color).size())
+    }
 }
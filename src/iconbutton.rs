@@ -59,6 +59,7 @@
 //! - Pressed/Active: Primary color background with highlighted border
 //!
 use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
 use crate::ui::{GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
 use core::marker::PhantomData;
@@ -240,6 +241,34 @@ impl<'a, ICON: IconoirIcon> IconButton<'a, ICON> {
     }
 }
 
+impl<ICON: IconoirIcon> IconButton<'_, ICON> {
+    /// Size of the button: the icon (and label, if set) plus padding and border,
+    /// at least as high as the default widget height and the given row height
+    fn size<COL: PixelColor>(&self, style: &Style<COL>, row_height: u32) -> Size {
+        let icon = ICON::new(style.icon_color);
+
+        let padding = style.spacing.button_padding;
+        let border = style.border_width;
+
+        let mut min_height = icon.bounding_box().size.height + 2 * padding.height + 2 * border;
+        let mut width = min_height;
+
+        if let Some(label) = self.label {
+            let text = Text::new(
+                label,
+                Point::new(0, 0),
+                MonoTextStyle::new(&style.default_font, style.text_color),
+            );
+            let text_size = text.bounding_box().size;
+            min_height += padding.height + text_size.height;
+            width = width.max(text_size.width + 2 * padding.width + 2 * border);
+        }
+
+        let height = max(max(style.default_widget_height, row_height), min_height);
+        Size::new(width, height)
+    }
+}
+
 impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
     /// Draws the icon button within the UI.
     ///
@@ -261,13 +290,11 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
         let padding = ui.style().spacing.button_padding;
         let border = ui.style().border_width;
 
-        let mut min_height = icon.bounding_box().size.height + 2 * padding.height + 2 * border;
-
-        let mut width = min_height;
+        let size = self.size(ui.style(), ui.get_row_height());
 
         let font = ui.style().default_font;
 
-        let mut text = if let Some(label) = self.label {
+        let mut text = self.label.map(|label| {
             let mut text = Text::new(
                 label,
                 Point::new(0, 0),
@@ -275,18 +302,8 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
             );
             text.text_style.alignment = Alignment::Center;
             text.text_style.baseline = Baseline::Top;
-            min_height += padding.height + text.bounding_box().size.height;
-            width = width.max(text.bounding_box().size.width + 2 * padding.width + 2 * border);
-            Some(text)
-        } else {
-            None
-        };
-        let height = max(
-            max(ui.style().default_widget_height, ui.get_row_height()),
-            min_height,
-        );
-
-        let size = Size::new(width, height);
+            text
+        });
 
         /*
         let icon = match size.width - 2 * padding.width {
@@ -298,7 +315,7 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
          */
 
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(size.width, max(size.height, height)))?;
+        let iresponse = ui.allocate_focusable_space(size)?;
 
         // translate icon
         let size = icon.bounding_box();
@@ -390,6 +407,10 @@ impl<ICON: IconoirIcon> Widget for IconButton<'_, ICON> {
 
         Ok(Response::new(iresponse).set_clicked(click).set_down(down))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        Some(self.size(style, 0))
+    }
 }
 
 // Implement common traits for IconButton
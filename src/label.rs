@@ -40,6 +40,7 @@
 //! ```
 
 use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
 use crate::ui::{GuiError, GuiResult, Response, Ui, Widget};
 use core::hash::BuildHasher;
 use core::hash::Hash;
@@ -219,30 +220,29 @@ impl Widget for Label<'_> {
         // get size of text
         let font = self.font.unwrap_or(ui.style().default_font);
         let text_style = MonoTextStyle::new(&font, ui.style().text_color);
-        let mut is_ellipsis = false;
-        let ellipsis_width = measure_string_width("...", text_style);
-        let mut text = self.text;
-
-        if let Some(width) = self.auto_truncate_width {
-            let (truncated_text, ellipsis_needed) =
-                truncate_text_singleline(text, text_style, width.min(ui.space_available().width));
-            text = truncated_text;
-            is_ellipsis = ellipsis_needed;
-        }
-
-        let mut text = Text::new(
-            text,
-            Point::new(0, 0),
-            MonoTextStyle::new(&font, ui.style().text_color),
+        let (text, is_ellipsis, size) = layout_text(
+            self.text,
+            text_style,
+            self.auto_truncate_width,
+            ui.space_available().width,
         );
 
-        let mut size = text.bounding_box();
-
-        if is_ellipsis {
-            size.size.width += ellipsis_width;
-        }
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(size.size.width, size.size.height))?;
+        let iresponse = ui.allocate_space_shrinkable(size)?;
+
+        // truncate the text if the label was shrunk to fit
+        let (text, is_ellipsis, size) = if iresponse.area.size.width < size.width {
+            layout_text(
+                self.text,
+                text_style,
+                Some(iresponse.area.size.width),
+                iresponse.area.size.width,
+            )
+        } else {
+            (text, is_ellipsis, size)
+        };
+
+        let mut text = Text::new(text, Point::new(0, 0), text_style);
 
         // check smartstate (a bool would work, but this is consistent with other widgets)
         let redraw = !self.smartstate.eq_option(&Some(Smartstate::state(0)));
@@ -251,7 +251,7 @@ impl Widget for Label<'_> {
         // move text (center vertically)
         text.translate_mut(iresponse.area.top_left.add(Point::new(
             0,
-            (iresponse.area.size.height - size.size.height) as i32 / 2,
+            (iresponse.area.size.height - size.height) as i32 / 2,
         )));
         text.text_style.baseline = Baseline::Top;
 
@@ -284,6 +284,18 @@ impl Widget for Label<'_> {
 
         Ok(Response::new(iresponse))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, available: Size) -> Option<Size> {
+        let font = self.font.unwrap_or(style.default_font);
+        let text_style = MonoTextStyle::new(&font, style.text_color);
+        let (_, _, size) = layout_text(
+            self.text,
+            text_style,
+            self.auto_truncate_width,
+            available.width,
+        );
+        Some(size)
+    }
 }
 
 /// A hasher for widgets that require hashing of data.
@@ -461,30 +473,29 @@ impl Widget for HashLabel<'_> {
         // get size of text
         let font = self.font.unwrap_or(ui.style().default_font);
         let text_style = MonoTextStyle::new(&font, ui.style().text_color);
-        let mut is_ellipsis = false;
-        let ellipsis_width = measure_string_width("...", text_style);
-        let mut text = self.text;
-
-        if let Some(width) = self.auto_truncate_width {
-            let (truncated_text, ellipsis_needed) =
-                truncate_text_singleline(text, text_style, width.min(ui.space_available().width));
-            text = truncated_text;
-            is_ellipsis = ellipsis_needed;
-        }
-
-        let mut text = Text::new(
-            text,
-            Point::new(0, 0),
-            MonoTextStyle::new(&font, ui.style().text_color),
+        let (text, is_ellipsis, size) = layout_text(
+            self.text,
+            text_style,
+            self.auto_truncate_width,
+            ui.space_available().width,
         );
 
-        let mut size = text.bounding_box();
-
-        if is_ellipsis {
-            size.size.width += ellipsis_width;
-        }
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(size.size.width, size.size.height))?;
+        let iresponse = ui.allocate_space_shrinkable(size)?;
+
+        // truncate the text if the label was shrunk to fit
+        let (text, is_ellipsis, size) = if iresponse.area.size.width < size.width {
+            layout_text(
+                self.text,
+                text_style,
+                Some(iresponse.area.size.width),
+                iresponse.area.size.width,
+            )
+        } else {
+            (text, is_ellipsis, size)
+        };
+
+        let mut text = Text::new(text, Point::new(0, 0), text_style);
 
         let hash = self.hasher.hash(self.text) as u32;
 
@@ -494,7 +505,7 @@ impl Widget for HashLabel<'_> {
         // move text (center vertically)
         text.translate_mut(iresponse.area.top_left.add(Point::new(
             0,
-            (iresponse.area.size.height - size.size.height) as i32 / 2,
+            (iresponse.area.size.height - size.height) as i32 / 2,
         )));
         text.text_style.baseline = Baseline::Top;
 
@@ -527,6 +538,41 @@ impl Widget for HashLabel<'_> {
 
         Ok(Response::new(iresponse))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, available: Size) -> Option<Size> {
+        let font = self.font.unwrap_or(style.default_font);
+        let text_style = MonoTextStyle::new(&font, style.text_color);
+        let (_, _, size) = layout_text(
+            self.text,
+            text_style,
+            self.auto_truncate_width,
+            available.width,
+        );
+        Some(size)
+    }
+}
+
+/// Lays out a single line of text, truncating it to `auto_truncate_width` (limited to `max_width`), if set.
+///
+/// ## Returns
+///
+/// The (possibly truncated) text, whether an ellipsis has to be appended, and the size of the text including the ellipsis.
+fn layout_text<'t, Color: PixelColor>(
+    text: &'t str,
+    font: MonoTextStyle<'_, Color>,
+    auto_truncate_width: Option<u32>,
+    max_width: u32,
+) -> (&'t str, bool, Size) {
+    let (text, is_ellipsis) = match auto_truncate_width {
+        Some(width) => truncate_text_singleline(text, font, width.min(max_width)),
+        None => (text, false),
+    };
+
+    let mut size = Text::new(text, Point::new(0, 0), font).bounding_box().size;
+    if is_ellipsis {
+        size.width += measure_string_width("...", font);
+    }
+    (text, is_ellipsis, size)
 }
 
 /// Measures the width of a string using the specified font style.
@@ -584,7 +630,9 @@ fn truncate_text_singleline<'a, Color: PixelColor>(
         }
 
         let per_char_width_estimate = font.font.character_size.width + font.font.character_spacing;
-        let chars_to_remove = (width - max_width - ellipsis_width) / per_char_width_estimate;
+        // always remove at least one char, even if the estimate rounds down to 0
+        let chars_to_remove =
+            (width.saturating_sub(max_width + ellipsis_width) / per_char_width_estimate).max(1);
         text = truncate_string_by(text, chars_to_remove as usize).unwrap_or("");
         width = measure_string_width(text, font);
         while width + ellipsis_width > max_width {
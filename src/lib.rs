@@ -5,21 +5,30 @@
 
 pub mod button;
 pub mod checkbox;
+pub mod collapsing_header;
 // mod icon;
 // pub mod icon;
 pub mod icon;
 pub mod label;
+pub mod scroll_area;
+pub mod separator;
 pub mod smartstate;
 pub mod spacer;
 pub mod style;
+pub mod tabs;
 // mod temp;
+pub mod flex;
 pub mod framebuf;
+pub mod gesture;
+pub mod grid;
+pub mod group;
 pub mod helpers;
 pub mod iconbutton;
 pub mod slider;
 pub mod toggle_button;
 pub mod toggle_switch;
 pub mod ui;
+pub mod window;
 
 pub mod prelude {
     pub use embedded_iconoir::prelude::*;
@@ -0,0 +1,505 @@
+//! # Scroll Area
+//!
+//! A [ScrollArea] shows content that is taller than the space it has on screen. The content is laid
+//! out in a (virtually unlimited) tall region, and only the part inside the visible rectangle is drawn.
+//! Dragging inside of the area scrolls it.
+//!
+//! ## Core Features
+//!
+//! - Vertical scrolling by dragging (touch-friendly)
+//! - Drawing and interaction are clipped to the visible rectangle
+//! - Scroll offset is kept in a caller-owned [ScrollState], so it persists between frames
+//! - Scrollbar indicating the visible part of the content
+//! - Smartstate support: scrolling only redraws the children of the scroll area
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::button::Button;
+//! # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+//! # use kolibri_embedded_gui::scroll_area::{ScrollArea, ScrollState};
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! // keep the state around between frames
+//! let mut scroll_state = ScrollState::new();
+//! let mut smartstates = SmartstateProvider::<20>::new();
+//!
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     smartstates.restart_counter();
+//!
+//!     ScrollArea::new(&mut scroll_state)
+//!         .height(150)
+//!         .show_with_smartstates(&mut ui, &mut smartstates, |ui, smartstates| {
+//!             for _ in 0..10 {
+//!                 ui.add(Button::new("Log entry").smartstate(smartstates.nxt()));
+//!             }
+//!             Ok(())
+//!         })
+//!         .unwrap();
+//!     # break;
+//! }
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! Children are placed in a child [Ui] that is moved up by the scroll offset, and whose drawing and
+//! interactions are clipped to the visible rectangle.
+//!
+//! A press inside the area only becomes a scroll once the pointer moved by a few pixels. From then on
+//! until the release, children don't receive the interaction, so e.g. a button the drag started on
+//! isn't clicked.
+//!
+//! When the offset changes, the visible rectangle is cleared and all children need to redraw. With
+//! [ScrollArea::show_with_smartstates], the smartstates the children used in the previous frame are
+//! forced to redraw, and no other smartstates are touched. The scroll area itself uses one smartstate
+//! (for its scrollbar), taken before the children's.
+
+use crate::smartstate::{Smartstate, SmartstateProvider};
+use crate::ui::{GuiResult, Interaction, Response, Ui};
+use core::cmp::{max, min};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+
+/// Width of the scrollbar
+const SCROLLBAR_WIDTH: u32 = 4;
+
+/// Space between the content and the scrollbar
+const SCROLLBAR_GAP: u32 = 2;
+
+/// Minimum height of the scrollbar thumb
+const MIN_THUMB_HEIGHT: u32 = 8;
+
+/// Distance the pointer has to be dragged before a press turns into a scroll
+const DRAG_THRESHOLD: u32 = 5;
+
+/// Height of the virtual region the content is laid out in
+const VIRTUAL_HEIGHT: u32 = i16::MAX as u32;
+
+/// Persistent state of a [ScrollArea].
+///
+/// Keep this around between frames (e.g. next to your [SmartstateProvider]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScrollState {
+    /// Current scroll offset (how far the content is moved up)
+    offset: u32,
+    /// Offset the content was last drawn with
+    drawn_offset: Option<u32>,
+    /// Height of the content in the last frame
+    content_height: u32,
+    /// Pointer y position and offset at the start of the current press
+    press: Option<(i32, u32)>,
+    /// Whether the current press is scrolling
+    scrolling: bool,
+    /// Number of smartstates used by the children in the last frame
+    smartstates: usize,
+}
+
+impl ScrollState {
+    /// Creates a new state, scrolled to the top
+    pub const fn new() -> Self {
+        ScrollState {
+            offset: 0,
+            drawn_offset: None,
+            content_height: 0,
+            press: None,
+            scrolling: false,
+            smartstates: 0,
+        }
+    }
+
+    /// Returns the current scroll offset in pixels
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Sets the scroll offset in pixels (e.g. to scroll to the top with `0`).
+    ///
+    /// The offset is limited to the content height when the area is shown.
+    pub fn set_offset(&mut self, offset: u32) {
+        self.offset = offset;
+    }
+
+    /// Scrolls by the given amount of pixels (positive values scroll down)
+    pub fn scroll_by(&mut self, delta: i32) {
+        self.offset = self.offset.saturating_add_signed(delta);
+    }
+
+    /// Returns whether the user is currently scrolling by dragging
+    pub fn is_scrolling(&self) -> bool {
+        self.scrolling
+    }
+
+    /// Returns the height of the content, as measured in the last frame
+    pub fn content_height(&self) -> u32 {
+        self.content_height
+    }
+}
+
+/// Vertically scrollable area. See the [module docs](crate::scroll_area) for details.
+pub struct ScrollArea<'s> {
+    state: &'s mut ScrollState,
+    height: Option<u32>,
+}
+
+impl<'s> ScrollArea<'s> {
+    /// Creates a new scroll area using the given (persistent) state.
+    ///
+    /// By default, the scroll area takes up all of the remaining space of the [Ui].
+    pub fn new(state: &'s mut ScrollState) -> Self {
+        ScrollArea {
+            state,
+            height: None,
+        }
+    }
+
+    /// Sets the height of the visible area
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Shows the scroll area, with `f` adding its content.
+    ///
+    /// Use this if the content doesn't use smartstates and is redrawn every frame.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the visible area. It's `changed` if the area was scrolled this frame,
+    /// and `down` while the user is scrolling.
+    pub fn show<DRAW, COL, F>(mut self, ui: &mut Ui<DRAW, COL>, f: F) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.show_inner(ui, None, |ui, _| f(ui))
+    }
+
+    /// Shows the scroll area, with `f` adding its content using the given smartstates.
+    ///
+    /// When the area is scrolled, only the smartstates used by `f` in the previous frame are forced to redraw.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the visible area. It's `changed` if the area was scrolled this frame,
+    /// and `down` while the user is scrolling.
+    pub fn show_with_smartstates<DRAW, COL, F, const N: usize>(
+        mut self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: &mut SmartstateProvider<N>,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        let mut bar_smartstate = *smartstates.nxt();
+        let bar_pos = smartstates.get_pos() - 1;
+        let previous_count = self.state.smartstates;
+        let mut count = 0;
+
+        let res = self.show_inner(ui, Some(&mut bar_smartstate), |ui, scrolled| {
+            if scrolled {
+                smartstates.force_redraw_range_relative(0..previous_count as i32);
+            }
+            let start = smartstates.get_pos();
+            let res = f(ui, smartstates);
+            count = smartstates.get_pos() - start;
+            res
+        });
+
+        *smartstates.get(bar_pos) = bar_smartstate;
+        self.state.smartstates = count;
+        res
+    }
+
+    fn show_inner<DRAW, COL, F>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        bar_smartstate: Option<&mut Smartstate>,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, bool) -> GuiResult<()>,
+    {
+        let available = ui.space_available();
+        let height = self.height.unwrap_or(available.height);
+        let iresponse = ui.allocate_space(Size::new(available.width, height))?;
+        let viewport = iresponse.area;
+        let state = &mut *self.state;
+
+        let max_offset = state.content_height.saturating_sub(viewport.size.height);
+
+        // scroll by dragging
+        let mut hide_interaction = state.scrolling;
+        match iresponse.interaction {
+            Interaction::Click(pt) => {
+                state.press = Some((pt.y, state.offset));
+                state.scrolling = false;
+                hide_interaction = false;
+            }
+            Interaction::Drag(pt) => {
+                if let Some((start_y, start_offset)) = state.press {
+                    let delta = start_y - pt.y;
+                    if state.scrolling || delta.unsigned_abs() >= DRAG_THRESHOLD {
+                        state.scrolling = true;
+                        hide_interaction = true;
+                        state.offset = start_offset.saturating_add_signed(delta);
+                    }
+                }
+            }
+            _ => {
+                state.press = None;
+                state.scrolling = false;
+            }
+        }
+        state.offset = min(state.offset, max_offset);
+
+        let offset = state.offset;
+        let scrolled = state.drawn_offset.is_some_and(|drawn| drawn != offset);
+
+        let content_width = viewport
+            .size
+            .width
+            .saturating_sub(SCROLLBAR_WIDTH + SCROLLBAR_GAP);
+        let content_clip = Rectangle::new(
+            viewport.top_left,
+            Size::new(content_width, viewport.size.height),
+        );
+        if scrolled {
+            ui.clear_area(content_clip)?;
+        }
+
+        let content = Rectangle::new(
+            viewport.top_left - Point::new(0, offset as i32),
+            Size::new(content_width, VIRTUAL_HEIGHT),
+        );
+        let (res, used) = ui.clipped_area_ui(content, content_clip, |ui| {
+            if hide_interaction {
+                ui.interact(Interaction::None);
+            }
+            f(ui, scrolled)
+        });
+
+        state.drawn_offset = Some(offset);
+        state.content_height = used.height;
+        // content got smaller, scroll up (takes effect next frame)
+        state.offset = min(
+            state.offset,
+            used.height.saturating_sub(viewport.size.height),
+        );
+
+        self.draw_scrollbar(ui, viewport, bar_smartstate)?;
+
+        res.map(|_| {
+            Response::new(iresponse)
+                .set_changed(scrolled)
+                .set_down(self.state.scrolling)
+        })
+    }
+
+    /// Draws the scrollbar on the right side of the `viewport`
+    fn draw_scrollbar<DRAW, COL>(
+        &self,
+        ui: &mut Ui<DRAW, COL>,
+        viewport: Rectangle,
+        smartstate: Option<&mut Smartstate>,
+    ) -> GuiResult<()>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let view_height = viewport.size.height;
+        let content_height = self.state.content_height;
+        let offset = self.state.offset;
+
+        if let Some(smartstate) = smartstate {
+            let bar_state = [offset, content_height, view_height];
+            if smartstate.is_state_hashed(&bar_state) && !ui.cleared() {
+                return Ok(());
+            }
+            smartstate.set_state_hashed(&bar_state);
+        }
+
+        let track = Rectangle::new(
+            viewport.top_left
+                + Point::new(
+                    viewport.size.width.saturating_sub(SCROLLBAR_WIDTH) as i32,
+                    0,
+                ),
+            Size::new(min(SCROLLBAR_WIDTH, viewport.size.width), view_height),
+        );
+        ui.clear_area(track)?;
+
+        if content_height <= view_height {
+            // everything is visible, no scrollbar needed
+            return Ok(());
+        }
+
+        let thumb_height = max(
+            view_height * view_height / content_height,
+            min(MIN_THUMB_HEIGHT, view_height),
+        );
+        let max_offset = content_height - view_height;
+        let thumb_y = (view_height - thumb_height) * min(offset, max_offset) / max_offset;
+        let thumb = Rectangle::new(
+            track.top_left + Point::new(0, thumb_y as i32),
+            Size::new(track.size.width, thumb_height),
+        );
+        let border_color = ui.style().border_color;
+        ui.draw(&thumb.into_styled(PrimitiveStyle::with_fill(border_color)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    /// Runs a frame with a 20px high scroll area holding five 10px high spacers.
+    ///
+    /// Returns the scroll area response and the interaction the first spacer received.
+    fn frame(state: &mut ScrollState, interaction: Interaction) -> (Response, Response) {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(interaction);
+
+        let mut first = None;
+        let response = ScrollArea::new(state)
+            .height(20)
+            .show(&mut ui, |ui| {
+                for _ in 0..5 {
+                    let response = ui.add(Spacer::new(Size::new(10, 10)));
+                    first.get_or_insert(response);
+                }
+                Ok(())
+            })
+            .unwrap();
+        (response, first.unwrap())
+    }
+
+    #[test]
+    fn test_scroll_by_dragging() {
+        let mut state = ScrollState::new();
+        frame(&mut state, Interaction::None);
+        // 10px for the first row, then four rows of default widget height, with 4px spacing in between
+        assert_eq!(state.content_height(), 90);
+
+        frame(&mut state, Interaction::Click(Point::new(10, 15)));
+        let (response, first) = frame(&mut state, Interaction::Drag(Point::new(10, 5)));
+        assert_eq!(state.offset(), 10);
+        assert!(response.changed());
+        assert!(response.down());
+        // the drag isn't passed on to the content
+        assert_eq!(first.internal.interaction, Interaction::None);
+
+        let (response, first) = frame(&mut state, Interaction::Release(Point::new(10, 5)));
+        assert!(!response.changed());
+        assert_eq!(first.internal.interaction, Interaction::None);
+        assert_eq!(first.internal.area.top_left, Point::new(3, -7));
+    }
+
+    #[test]
+    fn test_small_drag_is_passed_on() {
+        let mut state = ScrollState::new();
+        frame(&mut state, Interaction::None);
+        frame(&mut state, Interaction::Click(Point::new(5, 5)));
+        let (response, first) = frame(&mut state, Interaction::Drag(Point::new(5, 4)));
+        assert_eq!(state.offset(), 0);
+        assert!(!response.changed());
+        assert_eq!(
+            first.internal.interaction,
+            Interaction::Drag(Point::new(5, 4))
+        );
+    }
+
+    #[test]
+    fn test_offset_is_limited() {
+        let mut state = ScrollState::new();
+        frame(&mut state, Interaction::None);
+        state.scroll_by(1000);
+        frame(&mut state, Interaction::None);
+        assert_eq!(state.offset(), 90 - 20);
+    }
+
+    #[test]
+    fn test_interaction_outside_viewport_is_clipped() {
+        let mut state = ScrollState::new();
+        frame(&mut state, Interaction::None);
+        // the third spacer is below the visible area
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::Click(Point::new(5, 40)));
+        ScrollArea::new(&mut state)
+            .height(20)
+            .show(&mut ui, |ui| {
+                for i in 0..5 {
+                    let response = ui.add(Spacer::new(Size::new(10, 10)));
+                    if i == 2 {
+                        assert!(response.internal.area.contains(Point::new(5, 40)));
+                        assert_eq!(response.internal.interaction, Interaction::None);
+                    }
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_scroll_forces_redraw_of_children_only() {
+        let mut state = ScrollState::new();
+        let mut smartstates = SmartstateProvider::<10>::new();
+
+        // Runs a frame, returning whether the children's / following smartstates were unchanged
+        let mut frame = |interaction| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.interact(interaction);
+            smartstates.restart_counter();
+
+            let mut children_unchanged = true;
+            ScrollArea::new(&mut state)
+                .height(20)
+                .show_with_smartstates(&mut ui, &mut smartstates, |ui, smartstates| {
+                    for _ in 0..5 {
+                        ui.add(Spacer::new(Size::new(10, 10)));
+                        let smartstate = smartstates.nxt();
+                        children_unchanged &= *smartstate == Smartstate::state(1);
+                        smartstate.set_state(1);
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            let following = smartstates.nxt();
+            let following_unchanged = *following == Smartstate::state(1);
+            following.set_state(1);
+            (children_unchanged, following_unchanged)
+        };
+
+        frame(Interaction::None);
+        assert_eq!(frame(Interaction::None), (true, true));
+        frame(Interaction::Click(Point::new(10, 15)));
+        assert_eq!(frame(Interaction::Drag(Point::new(10, 5))), (false, true));
+        assert_eq!(frame(Interaction::Release(Point::new(10, 5))), (true, true));
+    }
+}
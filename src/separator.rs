@@ -0,0 +1,236 @@
+//! # Separator Widget
+//!
+//! The [Separator] widget draws a horizontal or vertical line to visually divide parts of the UI.
+//! Unlike a [Spacer](crate::spacer::Spacer), which only reserves space, it draws a visible rule.
+//!
+//! ## Core Features
+//!
+//! - Horizontal rules spanning the remaining width of the row ([Separator::horizontal])
+//! - Vertical rules spanning the height of the current row ([Separator::vertical])
+//! - Uses the style's border color and width (at least 1px)
+//! - Configurable margins on both sides of the line
+//! - Smartstate support for incremental redrawing
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use kolibri_embedded_gui::separator::Separator;
+//! # use kolibri_embedded_gui::label::Label;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//! ui.add_horizontal(Label::new("Speed"));
+//! ui.add_horizontal(Separator::vertical());
+//! ui.add(Label::new("Direction"));
+//!
+//! // divides the rows above and below, with 4px of space on each side of the line
+//! ui.add(Separator::horizontal().margin(4));
+//!
+//! ui.add(Label::new("Status"));
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! A horizontal separator allocates the remaining width of the current row, and a vertical separator
+//! the height of the current row (or the default widget height, if nothing was placed in the row yet).
+//! The margin is added on both sides of the line, across its direction. By default, the style's
+//! default padding is used as the margin.
+
+use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
+use crate::ui::{GuiError, GuiResult, Response, Ui, Widget};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+
+/// Direction of a [Separator]'s line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// A horizontal or vertical rule dividing parts of the UI.
+/// See the [module docs](crate::separator) for details.
+pub struct Separator<'a> {
+    direction: Direction,
+    margin: Option<u32>,
+    smartstate: Container<'a, Smartstate>,
+}
+
+impl<'a> Separator<'a> {
+    /// Creates a horizontal separator, spanning the remaining width of the row.
+    pub fn horizontal() -> Self {
+        Separator {
+            direction: Direction::Horizontal,
+            margin: None,
+            smartstate: Container::empty(),
+        }
+    }
+
+    /// Creates a vertical separator, spanning the height of the current row.
+    pub fn vertical() -> Self {
+        Separator {
+            direction: Direction::Vertical,
+            margin: None,
+            smartstate: Container::empty(),
+        }
+    }
+
+    /// Sets the space on both sides of the line (above and below a horizontal separator,
+    /// left and right of a vertical one).
+    ///
+    /// By default, the style's default padding is used.
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = Some(margin);
+        self
+    }
+
+    /// Adds a [Smartstate] to the separator for incremental redrawing.
+    ///
+    /// The separator only redraws when its size changes, or the smartstate is forced to redraw.
+    pub fn smartstate(mut self, smartstate: &'a mut Smartstate) -> Self {
+        self.smartstate.set(smartstate);
+        self
+    }
+
+    /// Returns the thickness of the line, and the margin on each side of it
+    fn thickness_and_margin<COL: PixelColor>(&self, style: &Style<COL>) -> (u32, u32) {
+        let margin = self.margin.unwrap_or(match self.direction {
+            Direction::Horizontal => style.spacing.default_padding.height,
+            Direction::Vertical => style.spacing.default_padding.width,
+        });
+        (max(style.border_width, 1), margin)
+    }
+}
+
+impl Widget for Separator<'_> {
+    fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+    ) -> GuiResult<Response> {
+        let (thickness, margin) = self.thickness_and_margin(ui.style());
+        let across = thickness + 2 * margin;
+        let size = match self.direction {
+            Direction::Horizontal => Size::new(ui.space_available().width, across),
+            Direction::Vertical => {
+                let row_height = ui.get_row_height();
+                let height = if row_height == 0 {
+                    ui.style().default_widget_height
+                } else {
+                    row_height
+                };
+                Size::new(across, height)
+            }
+        };
+
+        let iresponse = ui.allocate_space(size)?;
+        let area = iresponse.area;
+
+        // check smartstate (the length is the only thing that can change)
+        let length = match self.direction {
+            Direction::Horizontal => area.size.width,
+            Direction::Vertical => area.size.height,
+        };
+        let redraw = !self.smartstate.eq_option(&Some(Smartstate::state(length)));
+        self.smartstate.modify(|st| *st = Smartstate::state(length));
+
+        if redraw {
+            let line = match self.direction {
+                Direction::Horizontal => Rectangle::new(
+                    area.top_left + Point::new(0, margin as i32),
+                    Size::new(area.size.width, thickness),
+                ),
+                Direction::Vertical => Rectangle::new(
+                    area.top_left + Point::new(margin as i32, 0),
+                    Size::new(thickness, area.size.height),
+                ),
+            };
+
+            ui.start_drawing(&area);
+            if !ui.cleared() {
+                ui.clear_area(area)?;
+            }
+            let color = ui.style().border_color;
+            ui.draw(&line.into_styled(PrimitiveStyle::with_fill(color)))
+                .map_err(|_| GuiError::DrawError(Some("Couldn't draw separator")))?;
+            ui.finalize()?;
+        }
+
+        Ok(Response::new(iresponse).set_redraw(redraw))
+    }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, available: Size) -> Option<Size> {
+        let (thickness, margin) = self.thickness_and_margin(style);
+        match self.direction {
+            Direction::Horizontal => Some(Size::new(available.width, thickness + 2 * margin)),
+            // depends on the row the separator is placed in
+            Direction::Vertical => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    #[test]
+    fn test_horizontal_separator_spans_row() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        ui.add_horizontal(Spacer::new(Size::new(10, 5)));
+        let area = ui.add(Separator::horizontal().margin(2)).internal.area;
+
+        // remaining width after the spacer and the item spacing, 1px line with 2px margins
+        assert_eq!(area, Rectangle::new(Point::new(21, 3), Size::new(40, 5)));
+        assert_eq!(display.get_pixel(Point::new(30, 5)), Some(Rgb565::WHITE));
+    }
+
+    #[test]
+    fn test_vertical_separator_spans_row_height() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        ui.add_horizontal(Spacer::new(Size::new(10, 12)));
+        let area = ui
+            .add_horizontal(Separator::vertical().margin(0))
+            .internal
+            .area;
+        assert_eq!(area, Rectangle::new(Point::new(21, 3), Size::new(1, 12)));
+    }
+
+    #[test]
+    fn test_separator_smartstate() {
+        let mut smartstate = Smartstate::empty();
+        let mut frame = |width: u32| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.add_horizontal(Spacer::new(Size::new(width, 5)));
+            ui.add(Separator::horizontal().smartstate(&mut smartstate))
+                .redrawn()
+        };
+
+        assert!(frame(10));
+        assert!(!frame(10));
+        // the separator got shorter
+        assert!(frame(20));
+        assert!(!frame(20));
+    }
+}
@@ -8,7 +8,7 @@
 //! - Step-based value adjustments for precise control
 //! - Optional text labels for clear identification
 //! - Customizable width to fit various layouts
-//! - Visual feedback for different interaction states (normal, hover, active)
+//! - Visual feedback for different interaction states (normal, hover, active, edit mode)
 //!
 //! # Examples
 //!
@@ -58,12 +58,15 @@
 //!
 //! - Click and drag functionality for intuitive value selection
 //! - Step-based value snapping for precise control
+//! - Rotary encoder input: pressing the encoder on the focused slider enters the edit mode
+//!   (with a knob in the secondary color), in which each step of the encoder changes the value by the step size
 //! - Range constraints to keep values within bounds
 //! - Visual feedback through dynamic styling
 //! - Efficient fixed-point arithmetic for smooth value interpolation
 //!
 use crate::smartstate::{Container, Smartstate};
-use crate::ui::{GuiResult, Interaction, Response, Ui, Widget};
+use crate::style::Style;
+use crate::ui::{GuiResult, Interaction, LayoutDirection, Response, Ui, Widget};
 use core::cmp::max;
 use core::ops::RangeInclusive;
 use embedded_graphics::draw_target::DrawTarget;
@@ -74,6 +77,9 @@ use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{Circle, Line, PrimitiveStyleBuilder};
 use embedded_graphics::text::{Alignment, Baseline, Text};
 
+/// Diameter of the slider knob
+const SLIDER_KNOB_DIAMETER: u32 = 10;
+
 /// Performs linear interpolation using fixed-point arithmetic for embedded systems.
 ///
 /// This function interpolates between `start` and `end` values based on a given position `t`
@@ -192,6 +198,33 @@ impl<'a> Slider<'a> {
     }
 }
 
+impl Slider<'_> {
+    /// Size of the slider: the slider line (and label, if set) plus padding,
+    /// at least as high as the default widget height and the given row height
+    fn size<COL: PixelColor>(&self, style: &Style<COL>, row_height: u32) -> Size {
+        let padding = style.spacing.button_padding;
+
+        let mut height = max(
+            max(style.default_widget_height, row_height),
+            SLIDER_KNOB_DIAMETER + padding.height * 2,
+        );
+        let mut width = self.width + 2 * padding.width;
+
+        if let Some(label) = self.label {
+            let text = Text::new(
+                label,
+                Point::new(0, 0),
+                MonoTextStyle::new(&style.default_font, style.text_color),
+            );
+            let text_size = text.bounding_box().size;
+            height += padding.height + text_size.height;
+            width = width.max(text_size.width + 2 * padding.width);
+        }
+
+        Size::new(width, height)
+    }
+}
+
 impl Widget for Slider<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
@@ -202,17 +235,13 @@ impl Widget for Slider<'_> {
         let padding = ui.style().spacing.button_padding;
 
         let slider_thickness = 2;
-        let slider_knob_diameter = 10;
+        let slider_knob_diameter = SLIDER_KNOB_DIAMETER;
 
-        let mut height = max(
-            max(ui.style().default_widget_height, ui.get_row_height()),
-            slider_knob_diameter + padding.height * 2,
-        );
-
-        let mut width = self.width + 2 * padding.width;
+        let size = self.size(ui.style(), ui.get_row_height());
+        let width = size.width;
 
         let font = ui.style().default_font;
-        let mut text = if let Some(label) = self.label {
+        let mut text = self.label.map(|label| {
             let mut text = Text::new(
                 label,
                 Point::new(0, 0),
@@ -220,14 +249,8 @@ impl Widget for Slider<'_> {
             );
             text.text_style.alignment = Alignment::Center;
             text.text_style.baseline = Baseline::Top;
-            height += padding.height + text.bounding_box().size.height;
-            width = width.max(text.bounding_box().size.width + 2 * padding.width);
-            Some(text)
-        } else {
-            None
-        };
-
-        let size = Size::new(width, height);
+            text
+        });
 
         /*
         let icon = match size.width - 2 * padding.width {
@@ -239,7 +262,11 @@ impl Widget for Slider<'_> {
          */
 
         // allocate space
-        let iresponse = ui.allocate_space(Size::new(size.width, max(size.height, height)))?;
+        let (iresponse, editing) = ui.allocate_editable_space(size)?;
+
+        // in right-to-left layouts, the minimum is on the right
+        let rtl = ui.layout_direction() == LayoutDirection::RightToLeft;
+        let mirror = |x: i16| if rtl { width as i16 - x } else { x };
 
         // slider main line
         let slider_line = Line::new(
@@ -299,7 +326,7 @@ impl Widget for Slider<'_> {
                 let slider_val = lerp_fixed(
                     *self.range.start(),
                     *self.range.end(),
-                    point.x as i16 - iresponse.area.top_left.x as i16,
+                    mirror(point.x as i16 - iresponse.area.top_left.x as i16),
                     // + (slider_knob_diameter / 2) as i16,
                     padding.width as i16 + slider_knob_diameter as i16 / 2,
                     width as i16 - padding.width as i16 - slider_knob_diameter as i16 / 2,
@@ -314,17 +341,24 @@ impl Widget for Slider<'_> {
                     *self.value = (slider_val + to_prev).min(*self.range.end());
                 }
             }
+            // rotary encoder in edit mode
+            Interaction::Scroll(delta) => {
+                let slider_val = (*self.value as i32)
+                    .saturating_add(delta.saturating_mul(self.step_size.max(1) as i32));
+                *self.value =
+                    slider_val.clamp(*self.range.start() as i32, *self.range.end() as i32) as i16;
+            }
             _ => {}
         }
 
-        let slider_knob_pos = lerp_fixed(
+        let slider_knob_pos = mirror(lerp_fixed(
             // padding.width as i16,
             padding.width as i16 + slider_knob_diameter as i16 / 2,
             width as i16 - padding.width as i16 - slider_knob_diameter as i16 / 2,
             *self.value,
             *self.range.start(),
             *self.range.end(),
-        );
+        ));
 
         let slider_knob = Circle::with_center(
             Point::new(
@@ -337,14 +371,14 @@ impl Widget for Slider<'_> {
         );
 
         // old slider knob (for clearing)
-        let old_slider_knob_pos = lerp_fixed(
+        let old_slider_knob_pos = mirror(lerp_fixed(
             // padding.width as i16,
             padding.width as i16 + slider_knob_diameter as i16 / 2,
             width as i16 - padding.width as i16 - slider_knob_diameter as i16 / 2,
             old_val,
             *self.range.start(),
             *self.range.end(),
-        );
+        ));
 
         let old_slider_knob = Circle::with_center(
             Point::new(
@@ -359,6 +393,10 @@ impl Widget for Slider<'_> {
         // styles and smartstate
 
         let interact_val: u16 = match iresponse.interaction {
+            _ if editing => {
+                slider_knob_style.fill_color = Some(style.secondary_color);
+                3
+            }
             Interaction::Click(_) | Interaction::Drag(_) => {
                 slider_knob_style.fill_color = Some(style.primary_color);
                 2
@@ -372,7 +410,8 @@ impl Widget for Slider<'_> {
                 0
             }
         };
-        let state_val = (*self.value as u16) as u32 | ((interact_val as u32) << 16);
+        let state_val =
+            (*self.value as u16) as u32 | ((interact_val as u32) << 16) | ((rtl as u32) << 18);
 
         if !self.smartstate.eq_inner(&Smartstate::state(state_val)) {
             ui.start_drawing(&iresponse.area);
@@ -396,6 +435,10 @@ impl Widget for Slider<'_> {
 
         Ok(Response::new(iresponse).set_changed(old_val != *self.value)) //.set_clicked(click).set_down(down))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        Some(self.size(style, 0))
+    }
 }
 
 #[cfg(test)]
@@ -232,6 +232,11 @@ impl Smartstate {
         !self.1
     }
 
+    /// Returns the state ID, or `None` if this is an empty/invalid state.
+    pub(crate) fn get_state(&self) -> Option<u32> {
+        self.1.then_some(self.0)
+    }
+
     /// Returns true if this matches the given state ID and is valid.
     pub fn is_state(&self, state: u32) -> bool {
         self.1 && self.0 == state
@@ -49,6 +49,7 @@
 //! with the layout system to reserve space where nothing will be drawn.
 //!
 
+use crate::style::Style;
 use crate::ui::{GuiError, GuiResult, Response, Ui, Widget};
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::Size;
@@ -99,4 +100,12 @@ impl Widget for Spacer {
 
         Ok(Response::new(space))
     }
+
+    fn measure<COL: PixelColor>(&self, _style: &Style<COL>, _available: Size) -> Option<Size> {
+        match self.space {
+            SpacerType::Fixed(space) => Some(space),
+            // depends on the position the spacer is placed at
+            SpacerType::RightBound(_) => None,
+        }
+    }
 }
@@ -0,0 +1,736 @@
+//! # Tabs
+//!
+//! [Tabs] show one of several pages, selected by a strip of tabs above them. A typical use is a
+//! device with status, settings and diagnostics pages.
+//!
+//! ## Core Features
+//!
+//! - Tab strip with text labels ([TabBar::tab]) or Iconoir icons ([TabBar::icon_tab])
+//! - The selected tab is kept in a caller-owned `usize`, so it persists between frames
+//! - The active page is drawn in a sub-[Ui] below the strip
+//! - Smartstate support: tabs only redraw when they change, and the page is fully redrawn when
+//!   the selected tab changes
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use embedded_iconoir::size12px;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::button::Button;
+//! # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+//! # use kolibri_embedded_gui::tabs::Tabs;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! // keep the selected tab around between frames
+//! let mut tab = 0;
+//! let mut smartstates = SmartstateProvider::<20>::new();
+//!
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     smartstates.restart_counter();
+//!
+//!     Tabs::new(&mut tab)
+//!         .show_with_smartstates(
+//!             &mut ui,
+//!             &mut smartstates,
+//!             |bar| {
+//!                 bar.tab("Status");
+//!                 bar.tab("Settings");
+//!                 bar.icon_tab::<size12px::system::Settings>();
+//!             },
+//!             |ui, tab, smartstates| {
+//!                 match tab {
+//!                     0 => ui.add(Label::new("All good").smartstate(smartstates.nxt())),
+//!                     1 => ui.add(Button::new("Reset").smartstate(smartstates.nxt())),
+//!                     _ => ui.add(Label::new("Diagnostics").smartstate(smartstates.nxt())),
+//!                 };
+//!                 Ok(())
+//!             },
+//!         )
+//!         .unwrap();
+//!     # break;
+//! }
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! The tab strip takes up a full row of the [Ui]. Tabs are drawn immediately, so when a tab is
+//! selected, the tabs before it have already been drawn with the old selection. They are redrawn in
+//! the next frame, as their smartstates (or, without smartstates, every frame) redraw them.
+//!
+//! The page is drawn in a child [Ui] below the strip, and a new row is started after it. When the selected tab
+//! changes, the page area is cleared. With [Tabs::show_with_smartstates], the old page's height and number of
+//! smartstates are kept, so only the old page is cleared, and only the page's smartstates are forced to redraw.
+//! The widgets after the page are only forced to redraw if the new page uses another number of smartstates, and
+//! only cleared if it has another height (so they moved). Each tab uses one smartstate, followed by one for the
+//! page's height and smartstate count.
+
+use crate::smartstate::{Smartstate, SmartstateProvider};
+use crate::ui::{GuiError, GuiResult, Interaction, InternalResponse, Response, Ui};
+use core::cmp::{max, min};
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::image::Image;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle, RoundedRectangle};
+use embedded_graphics::text::{Baseline, Text};
+use embedded_iconoir::prelude::{IconoirIcon, IconoirNewIcon};
+
+/// Smartstates used by [Tabs], so [TabBar] doesn't depend on the size of the [SmartstateProvider].
+///
+/// Implemented by [SmartstateProvider], and by `()` for tabs without smartstates.
+trait TabSmartstates {
+    /// Returns the smartstate for the next tab, if smartstates are used
+    fn next_smartstate(&mut self) -> Option<&mut Smartstate>;
+    /// Returns the current position
+    fn position(&self) -> usize;
+    /// Returns the smartstate at the (absolute) position `pos`, if smartstates are used
+    fn smartstate_at(&mut self, pos: usize) -> Option<&mut Smartstate>;
+    /// Forces the `count` smartstates from the (absolute) position `start` on to redraw
+    fn force_redraw_range(&mut self, start: usize, count: usize);
+    /// Forces all smartstates from the current position on to redraw
+    fn force_redraw_remaining(&mut self);
+}
+
+impl TabSmartstates for () {
+    fn next_smartstate(&mut self) -> Option<&mut Smartstate> {
+        None
+    }
+
+    fn position(&self) -> usize {
+        0
+    }
+
+    fn smartstate_at(&mut self, _pos: usize) -> Option<&mut Smartstate> {
+        None
+    }
+
+    fn force_redraw_range(&mut self, _start: usize, _count: usize) {}
+
+    fn force_redraw_remaining(&mut self) {}
+}
+
+impl<const N: usize> TabSmartstates for SmartstateProvider<N> {
+    fn next_smartstate(&mut self) -> Option<&mut Smartstate> {
+        Some(self.nxt())
+    }
+
+    fn position(&self) -> usize {
+        self.get_pos()
+    }
+
+    fn smartstate_at(&mut self, pos: usize) -> Option<&mut Smartstate> {
+        Some(self.get(pos))
+    }
+
+    fn force_redraw_range(&mut self, start: usize, count: usize) {
+        SmartstateProvider::force_redraw_range(self, start..min(start + count, N));
+    }
+
+    fn force_redraw_remaining(&mut self) {
+        SmartstateProvider::force_redraw_remaining(self);
+    }
+}
+
+/// State of the page of [Tabs] in the last frame: its height, and the number of smartstates it used
+fn page_state(height: u32, count: usize) -> Smartstate {
+    Smartstate::state((min(count, 0xFFFF) as u32) << 16 | min(height, 0xFFFF))
+}
+
+/// Container showing a strip of tabs, and the page of the selected tab below it.
+/// See the [module docs](crate::tabs) for details.
+pub struct Tabs<'t> {
+    selected: &'t mut usize,
+    height: Option<u32>,
+}
+
+impl<'t> Tabs<'t> {
+    /// Creates new tabs, with `selected` being the index of the selected tab.
+    pub fn new(selected: &'t mut usize) -> Self {
+        Tabs {
+            selected,
+            height: None,
+        }
+    }
+
+    /// Sets the height of the tab strip (e.g. for larger icons).
+    ///
+    /// By default, the strip is as high as a [Button](crate::button::Button).
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Draws the tab strip using `tabs`, and the page of the selected tab using `page`.
+    ///
+    /// `page` gets a sub-[Ui] below the strip and the index of the selected tab.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the tab strip. It's `changed` if another tab was selected this frame.
+    pub fn show<DRAW, COL, T, F>(
+        self,
+        ui: &mut Ui<DRAW, COL>,
+        tabs: T,
+        page: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        T: FnOnce(&mut TabBar<'_, '_, DRAW, COL>),
+        F: FnOnce(&mut Ui<DRAW, COL>, usize) -> GuiResult<()>,
+    {
+        self.show_inner(ui, &mut (), tabs, |ui, selected, _| page(ui, selected))
+    }
+
+    /// Like [Tabs::show], but the tabs use smartstates from `smartstates`, and `page` gets the provider as well.
+    ///
+    /// When another tab is selected, the smartstates used by the old page are forced to redraw. The smartstates after
+    /// the page are only forced to redraw if the new page uses another number of smartstates or has another height
+    /// than the old one.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the tab strip. It's `changed` if another tab was selected this frame.
+    pub fn show_with_smartstates<DRAW, COL, T, F, const N: usize>(
+        self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: &mut SmartstateProvider<N>,
+        tabs: T,
+        page: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        T: FnOnce(&mut TabBar<'_, '_, DRAW, COL>),
+        F: FnOnce(&mut Ui<DRAW, COL>, usize, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        self.show_inner(ui, smartstates, tabs, page)
+    }
+
+    fn show_inner<DRAW, COL, S, T, F>(
+        self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: &mut S,
+        tabs: T,
+        page: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        S: TabSmartstates,
+        T: FnOnce(&mut TabBar<'_, '_, DRAW, COL>),
+        F: FnOnce(&mut Ui<DRAW, COL>, usize, &mut S) -> GuiResult<()>,
+    {
+        let height = self.height.unwrap_or_else(|| strip_height(ui));
+        let width = ui.space_available().width;
+        let iresponse = ui.allocate_space(Size::new(width, height))?;
+        ui.new_row();
+
+        let mut bar = TabBar::new(ui, iresponse.area, *self.selected, smartstates);
+        tabs(&mut bar);
+        let (selected, error) = (bar.selected, bar.error);
+        let changed = selected != *self.selected;
+        *self.selected = selected;
+
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        // the height of the page and the number of its smartstates in the last frame are kept in a
+        // smartstate, to clear only the old page and force only its smartstates to redraw
+        let page_smartstate = smartstates.next_smartstate().copied();
+        let page_pos = smartstates.position().wrapping_sub(1);
+        let page_start = smartstates.position();
+        let old_state = page_smartstate
+            .and_then(|state| state.get_state())
+            .map(|state| (state & 0xFFFF, (state >> 16) as usize));
+
+        let free = ui.free_area();
+        if changed {
+            // remove the old page, and redraw all widgets of the new one
+            match old_state {
+                Some((height, count)) => {
+                    ui.clear_area(Rectangle::new(
+                        free.top_left,
+                        Size::new(free.size.width, min(height, free.size.height)),
+                    ))?;
+                    smartstates.force_redraw_range(page_start, count);
+                }
+                None => {
+                    ui.clear_area(free)?;
+                    smartstates.force_redraw_remaining();
+                }
+            }
+        }
+
+        let (res, used) = ui.area_ui(free, |ui| page(ui, selected, smartstates));
+        let count = smartstates.position() - page_start;
+        if let Some(state) = smartstates.smartstate_at(page_pos) {
+            *state = page_state(used.height, count);
+        }
+        ui.allocate_at_cursor(used)?;
+        ui.new_row();
+
+        if changed {
+            if let Some((old_height, old_count)) = old_state {
+                if count > old_count {
+                    // the new page used smartstates of the widgets after the old one, which might have
+                    // matched by chance, so its widgets using them are redrawn in the next frame
+                    smartstates.force_redraw_range(page_start + old_count, count - old_count);
+                }
+                if count != old_count {
+                    // the widgets after the page got other smartstates
+                    smartstates.force_redraw_remaining();
+                }
+                if old_height != used.height {
+                    // the widgets after the page moved
+                    smartstates.force_redraw_remaining();
+                    ui.clear_area(ui.free_area())?;
+                }
+            }
+        }
+        res?;
+
+        Ok(Response::new(iresponse).set_changed(changed))
+    }
+}
+
+/// Default height of the tab strip: the height of a button
+fn strip_height<DRAW, COL>(ui: &Ui<DRAW, COL>) -> u32
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    let style = ui.style();
+    let text_height = style.default_font.character_size.height;
+    max(
+        text_height + 2 * style.spacing.button_padding.height + 2 * style.border_width,
+        style.default_widget_height,
+    )
+}
+
+/// Strip of tabs, passed to the `tabs` closure of [Tabs::show] and [Tabs::show_with_smartstates].
+pub struct TabBar<'b, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    ui: &'b mut Ui<'a, DRAW, COL>,
+    /// Area of the strip (absolute)
+    area: Rectangle,
+    /// Index of the selected tab
+    selected: usize,
+    smartstates: &'b mut dyn TabSmartstates,
+    /// Index of the next tab
+    index: usize,
+    /// X offset of the next tab, relative to the strip
+    x: u32,
+    /// First error that occurred while drawing the tabs
+    error: Option<GuiError>,
+}
+
+impl<'b, 'a, DRAW, COL> TabBar<'b, 'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    fn new(
+        ui: &'b mut Ui<'a, DRAW, COL>,
+        area: Rectangle,
+        selected: usize,
+        smartstates: &'b mut dyn TabSmartstates,
+    ) -> Self {
+        TabBar {
+            ui,
+            area,
+            selected,
+            smartstates,
+            index: 0,
+            x: 0,
+            error: None,
+        }
+    }
+
+    /// Adds a tab with a text label.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the tab. It's `clicked` and `changed` when the tab gets selected.
+    pub fn tab(&mut self, label: &str) -> Response {
+        let style = self.ui.style();
+        let font = style.default_font;
+        let mut text = Text::new(
+            label,
+            Point::zero(),
+            MonoTextStyle::new(&font, style.text_color),
+        );
+        text.text_style.baseline = Baseline::Top;
+        let text_size = text.bounding_box().size;
+        let padding = style.spacing.button_padding.width + style.border_width;
+
+        self.add_tab(text_size.width + 2 * padding, |ui, area| {
+            text.translate_mut(
+                area.top_left
+                    + Point::new(
+                        padding as i32,
+                        (area.size.height.saturating_sub(text_size.height) / 2) as i32,
+                    ),
+            );
+            ui.draw(&text)
+                .map_err(|_| GuiError::DrawError(Some("Couldn't draw tab label")))
+        })
+    }
+
+    /// Adds a tab with an Iconoir icon, given by its type (e.g. `bar.icon_tab::<size12px::system::Settings>()`).
+    ///
+    /// If the icon is higher than the tab strip, increase its height with [Tabs::height].
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the tab. It's `clicked` and `changed` when the tab gets selected.
+    pub fn icon_tab<ICON: IconoirIcon>(&mut self) -> Response {
+        let style = self.ui.style();
+        let icon = ICON::new(style.icon_color);
+        let icon_size = icon.bounding_box().size;
+        let padding = style.spacing.button_padding.width + style.border_width;
+
+        self.add_tab(icon_size.width + 2 * padding, |ui, area| {
+            let pos = area.top_left
+                + Point::new(
+                    padding as i32,
+                    (area.size.height.saturating_sub(icon_size.height) / 2) as i32,
+                );
+            ui.draw(&Image::new(&icon, pos))
+                .map_err(|_| GuiError::DrawError(Some("Couldn't draw tab icon")))
+        })
+    }
+
+    /// Draws the frame of the next tab with the given width, and its content using `draw_content`
+    fn add_tab(
+        &mut self,
+        width: u32,
+        draw_content: impl FnOnce(&mut Ui<'a, DRAW, COL>, Rectangle) -> GuiResult<()>,
+    ) -> Response {
+        let index = self.index;
+        self.index += 1;
+        let smartstate = self.smartstates.next_smartstate();
+
+        if self.x + width > self.area.size.width {
+            self.error.get_or_insert(GuiError::NoSpaceLeft);
+            return Response::from_error(GuiError::NoSpaceLeft);
+        }
+        let area = self.ui.mirrored(
+            self.area,
+            Rectangle::new(
+                self.area.top_left + Point::new(self.x as i32, 0),
+                Size::new(width, self.area.size.height),
+            ),
+        );
+        self.x += width;
+
+        let interaction = self.ui.check_interact(area);
+
+        let clicked = matches!(interaction, Interaction::Release(_));
+        let changed = clicked && self.selected != index;
+        if clicked {
+            self.selected = index;
+        }
+        let selected = self.selected == index;
+
+        // state: selected, and the kind of interaction
+        let interaction_state = match interaction {
+            Interaction::Click(_) | Interaction::Drag(_) | Interaction::Release(_) => 1,
+            Interaction::Hover(_) => 2,
+            _ => 0,
+        };
+        let state = Smartstate::state((selected as u32) << 2 | interaction_state);
+        let redraw = match smartstate {
+            Some(smartstate) => {
+                let redraw = *smartstate != state;
+                *smartstate = state;
+                redraw
+            }
+            None => true,
+        };
+
+        if redraw {
+            if let Err(e) = self.draw_tab(area, selected, interaction_state, draw_content) {
+                self.error.get_or_insert(e);
+                return Response::from_error(e);
+            }
+        }
+
+        Response::new(InternalResponse::new(area, interaction))
+            .set_clicked(clicked)
+            .set_changed(changed)
+            .set_down(interaction_state == 1)
+            .set_redraw(redraw)
+    }
+
+    fn draw_tab(
+        &mut self,
+        area: Rectangle,
+        selected: bool,
+        interaction_state: u32,
+        draw_content: impl FnOnce(&mut Ui<'a, DRAW, COL>, Rectangle) -> GuiResult<()>,
+    ) -> GuiResult<()> {
+        let style = self.ui.style();
+        let (stroke_color, stroke_width) = if interaction_state != 0 {
+            (style.highlight_border_color, style.highlight_border_width)
+        } else {
+            (style.border_color, style.border_width)
+        };
+        let fill_color = match (selected, interaction_state) {
+            (true, _) | (false, 1) => style.primary_color,
+            (false, 2) => style.highlight_item_background_color,
+            (false, _) => style.item_background_color,
+        };
+        let frame_style = PrimitiveStyleBuilder::new()
+            .stroke_color(stroke_color)
+            .stroke_width(stroke_width)
+            .fill_color(fill_color)
+            .build();
+        let corner_radius = style.corner_radius;
+
+        self.ui.start_drawing(&area);
+        if !self.ui.cleared() {
+            self.ui.clear_area(area)?;
+        }
+        self.ui
+            .draw(
+                &RoundedRectangle::with_equal_corners(
+                    area,
+                    Size::new(corner_radius, corner_radius),
+                )
+                .into_styled(frame_style),
+            )
+            .map_err(|_| GuiError::DrawError(Some("Couldn't draw tab")))?;
+        draw_content(self.ui, area)?;
+        self.ui.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label::Label;
+    use crate::style::medsize_rgb565_style;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    /// Draws one frame with two tabs, returning the response of the strip, the tab areas, the page area and
+    /// whether the page had to be redrawn
+    fn frame(
+        selected: &mut usize,
+        interaction: Interaction,
+        smartstates: &mut SmartstateProvider<4>,
+    ) -> (Response, [Rectangle; 2], Rectangle, bool) {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(interaction);
+        smartstates.restart_counter();
+
+        let mut tabs = [Rectangle::zero(); 2];
+        let mut page = Rectangle::zero();
+        let mut page_redraw = false;
+        let response = Tabs::new(selected)
+            .show_with_smartstates(
+                &mut ui,
+                smartstates,
+                |bar| {
+                    tabs[0] = bar.tab("A").internal.area;
+                    tabs[1] = bar.tab("B").internal.area;
+                },
+                |ui, _, smartstates| {
+                    page_redraw = *smartstates.get_relative(0) != Smartstate::state(0);
+                    page = ui
+                        .add(Label::new("P").smartstate(smartstates.nxt()))
+                        .internal
+                        .area;
+                    Ok(())
+                },
+            )
+            .unwrap();
+        (response, tabs, page, page_redraw)
+    }
+
+    #[test]
+    fn test_tabs_select() {
+        let mut selected = 0;
+        let mut smartstates = SmartstateProvider::<4>::new();
+
+        let (response, tabs, page, _) = frame(&mut selected, Interaction::None, &mut smartstates);
+        assert!(!response.changed());
+        assert_eq!(tabs[0].top_left, Point::new(3, 3));
+        assert_eq!(
+            tabs[1].top_left.x,
+            tabs[0].top_left.x + tabs[0].size.width as i32
+        );
+        // the page goes below the strip
+        assert!(page.top_left.y > tabs[0].bottom_right().unwrap().y);
+
+        let (response, _, _, _) = frame(
+            &mut selected,
+            Interaction::Release(tabs[1].center()),
+            &mut smartstates,
+        );
+        assert!(response.changed());
+        assert_eq!(selected, 1);
+
+        // selecting the same tab again doesn't change anything
+        let (response, _, _, _) = frame(
+            &mut selected,
+            Interaction::Release(tabs[1].center()),
+            &mut smartstates,
+        );
+        assert!(!response.changed());
+        assert_eq!(selected, 1);
+    }
+
+    #[test]
+    fn test_tabs_old_selection_redrawn_next_frame() {
+        let mut selected = 0;
+        let mut smartstates = SmartstateProvider::<4>::new();
+
+        // returns the tab areas, whether the tabs were redrawn, and how often the strip closure was called
+        let mut frame = |selected: &mut usize, interaction: Interaction| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.interact(interaction);
+            smartstates.restart_counter();
+
+            let mut tabs = [Rectangle::zero(); 2];
+            let mut redraw = [false; 2];
+            let mut calls = 0;
+            Tabs::new(selected)
+                .show_with_smartstates(
+                    &mut ui,
+                    &mut smartstates,
+                    |bar| {
+                        calls += 1;
+                        for (i, label) in ["A", "B"].into_iter().enumerate() {
+                            let response = bar.tab(label);
+                            tabs[i] = response.internal.area;
+                            redraw[i] = response.redrawn();
+                        }
+                    },
+                    |_, _, _| Ok(()),
+                )
+                .unwrap();
+            (tabs, redraw, calls)
+        };
+
+        let (tabs, _, _) = frame(&mut selected, Interaction::None);
+        let (_, redraw, _) = frame(&mut selected, Interaction::None);
+        assert_eq!(redraw, [false, false]);
+
+        // the strip isn't replayed when the selection changes
+        let (_, redraw, calls) = frame(&mut selected, Interaction::Release(tabs[1].center()));
+        assert_eq!(selected, 1);
+        assert_eq!(calls, 1);
+        assert_eq!(redraw, [false, true]);
+
+        // the old tab is redrawn unselected in the next frame instead
+        let (_, redraw, _) = frame(&mut selected, Interaction::None);
+        assert_eq!(redraw, [true, true]);
+        let (_, redraw, _) = frame(&mut selected, Interaction::None);
+        assert_eq!(redraw, [false, false]);
+    }
+
+    #[test]
+    fn test_tabs_redraw_page_on_change() {
+        let mut selected = 0;
+        let mut smartstates = SmartstateProvider::<4>::new();
+
+        let (_, tabs, _, page_redraw) = frame(&mut selected, Interaction::None, &mut smartstates);
+        assert!(page_redraw);
+        let (_, _, _, page_redraw) = frame(&mut selected, Interaction::None, &mut smartstates);
+        assert!(!page_redraw);
+
+        // the page is redrawn when the tab changes, even though its widget didn't change
+        let (_, _, _, page_redraw) = frame(
+            &mut selected,
+            Interaction::Release(tabs[1].center()),
+            &mut smartstates,
+        );
+        assert_eq!(selected, 1);
+        assert!(page_redraw);
+
+        let (_, _, _, page_redraw) = frame(&mut selected, Interaction::None, &mut smartstates);
+        assert!(!page_redraw);
+    }
+
+    #[test]
+    fn test_tabs_change_only_redraws_page() {
+        let mut selected = 0;
+        let mut smartstates = SmartstateProvider::<8>::new();
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+
+        // shows the tabs, a page with `rows` rows, and a spacer after them,
+        // returning the tab areas and whether the smartstate after the page was forced to redraw
+        let mut frame = |display: &mut MockDisplay<Rgb565>,
+                         selected: &mut usize,
+                         interaction: Interaction,
+                         rows: usize| {
+            let mut ui = Ui::new_fullscreen(display, medsize_rgb565_style());
+            ui.interact(interaction);
+            smartstates.restart_counter();
+
+            let mut tabs = [Rectangle::zero(); 2];
+            Tabs::new(selected)
+                .show_with_smartstates(
+                    &mut ui,
+                    &mut smartstates,
+                    |bar| {
+                        tabs[0] = bar.tab("A").internal.area;
+                        tabs[1] = bar.tab("B").internal.area;
+                    },
+                    |ui, _, smartstates| {
+                        for _ in 0..rows {
+                            ui.add(Label::new("P").smartstate(smartstates.nxt()));
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            let after = smartstates.nxt();
+            let after_forced = after.is_empty();
+            *after = Smartstate::state(1);
+            (tabs, after_forced)
+        };
+
+        let (tabs, after_forced) = frame(&mut display, &mut selected, Interaction::None, 1);
+        assert!(after_forced);
+        // something drawn after the page
+        display.set_pixel(Point::new(10, 55), Some(Rgb565::RED));
+
+        // a page with the same height: the widgets after it stay as they are
+        let release = Interaction::Release(tabs[1].center());
+        let (_, after_forced) = frame(&mut display, &mut selected, release, 1);
+        assert_eq!(selected, 1);
+        assert!(!after_forced);
+        assert_eq!(display.get_pixel(Point::new(10, 55)), Some(Rgb565::RED));
+
+        // an empty page moves them up, so they are cleared and redrawn
+        let release = Interaction::Release(tabs[0].center());
+        let (_, after_forced) = frame(&mut display, &mut selected, release, 0);
+        assert_eq!(selected, 0);
+        assert!(after_forced);
+        assert_eq!(
+            display.get_pixel(Point::new(10, 55)),
+            Some(medsize_rgb565_style().background_color)
+        );
+    }
+}
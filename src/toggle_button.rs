@@ -10,6 +10,7 @@
 //! with the framework's [Smartstate] system for efficient rendering.
 //!
 use crate::smartstate::{Container, Smartstate};
+use crate::style::Style;
 use crate::ui::{GuiError, GuiResult, Interaction, Response, Ui, Widget};
 use core::cmp::max;
 use embedded_graphics::draw_target::DrawTarget;
@@ -132,6 +133,28 @@ impl<'a> ToggleButton<'a> {
     }
 }
 
+impl ToggleButton<'_> {
+    /// Size of the button: the label plus padding and border, at least as high as the default widget height
+    fn size<COL: PixelColor>(&self, style: &Style<COL>) -> Size {
+        let text = Text::new(
+            self.label,
+            Point::zero(),
+            MonoTextStyle::new(&style.default_font, style.text_color),
+        );
+        let text_bounds = text.bounding_box();
+        let padding = style.spacing.button_padding;
+        let border = style.border_width;
+
+        Size::new(
+            text_bounds.size.width + 2 * padding.width + 2 * border,
+            max(
+                text_bounds.size.height + 2 * padding.height + 2 * border,
+                style.default_widget_height,
+            ),
+        )
+    }
+}
+
 impl Widget for ToggleButton<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
@@ -146,21 +169,12 @@ impl Widget for ToggleButton<'_> {
         );
 
         // Determine size
-        let text_bounds = text.bounding_box();
         let padding = ui.style().spacing.button_padding;
         let border = ui.style().border_width;
-        let height = ui.style().default_widget_height;
-
-        let size = Size::new(
-            text_bounds.size.width + 2 * padding.width + 2 * border,
-            max(
-                text_bounds.size.height + 2 * padding.height + 2 * border,
-                height,
-            ),
-        );
+        let size = self.size(ui.style());
 
         // Allocate space
-        let iresponse = ui.allocate_space(size)?;
+        let iresponse = ui.allocate_focusable_space(size)?;
 
         // Position text
         text.translate_mut(
@@ -263,4 +277,8 @@ impl Widget for ToggleButton<'_> {
             .set_down(down)
             .set_changed(changed))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        Some(self.size(style))
+    }
 }
@@ -10,7 +10,8 @@
 //! with the framework's [Smartstate] system for efficient rendering.
 
 use crate::smartstate::{Container, Smartstate};
-use crate::ui::{GuiError, GuiResult, Interaction, Response, Ui, Widget};
+use crate::style::Style;
+use crate::ui::{GuiError, GuiResult, Interaction, LayoutDirection, Response, Ui, Widget};
 use core::cmp::max;
 use embedded_graphics::draw_target::DrawTarget;
 use embedded_graphics::geometry::{Point, Size};
@@ -155,6 +156,17 @@ impl<'a> ToggleSwitch<'a> {
     }
 }
 
+impl ToggleSwitch<'_> {
+    /// Size of the switch including padding
+    fn size<COL: PixelColor>(&self, style: &Style<COL>) -> Size {
+        let padding = style.spacing.button_padding;
+        Size::new(
+            self.width + 2 * padding.width,
+            self.height + 2 * padding.height,
+        )
+    }
+}
+
 impl Widget for ToggleSwitch<'_> {
     fn draw<DRAW: DrawTarget<Color = COL>, COL: PixelColor>(
         &mut self,
@@ -162,13 +174,10 @@ impl Widget for ToggleSwitch<'_> {
     ) -> GuiResult<Response> {
         // Calculate total size including padding
         let padding = ui.style().spacing.button_padding;
-        let total_size = Size::new(
-            self.width + 2 * padding.width,
-            self.height + 2 * padding.height,
-        );
+        let total_size = self.size(ui.style());
 
         // Allocate space in the UI
-        let iresponse = ui.allocate_space(total_size)?;
+        let iresponse = ui.allocate_focusable_space(total_size)?;
 
         // Handle interaction
         let mut changed = false;
@@ -200,6 +209,9 @@ impl Widget for ToggleSwitch<'_> {
 
         let prevstate = self.smartstate.clone_inner();
 
+        // in right-to-left layouts, the knob is on the left when active
+        let rtl = ui.layout_direction() == LayoutDirection::RightToLeft;
+
         // Determine state based on both toggle state and interaction
         let state = match (iresponse.interaction, *self.active) {
             (Interaction::Click(_) | Interaction::Drag(_), true) => 1,
@@ -208,7 +220,7 @@ impl Widget for ToggleSwitch<'_> {
             (Interaction::Hover(_), false) => 4,
             (_, true) => 5,
             (_, false) => 6,
-        };
+        } | (rtl as u32) << 3;
 
         self.smartstate.modify(|st| *st = Smartstate::state(state));
 
@@ -239,7 +251,7 @@ impl Widget for ToggleSwitch<'_> {
 
             // Calculate knob position
             let knob_radius = (self.height / 2) - ui.style().border_width;
-            let knob_x = if *self.active {
+            let knob_x = if *self.active != rtl {
                 // Positioned on the right
                 iresponse.area.top_left.x + padding.width as i32 + self.width as i32
                     - knob_radius as i32
@@ -282,4 +294,8 @@ impl Widget for ToggleSwitch<'_> {
             .set_down(down)
             .set_changed(changed))
     }
+
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, _available: Size) -> Option<Size> {
+        Some(self.size(style))
+    }
 }
@@ -1,4 +1,7 @@
+use crate::flex::FlexRow;
 use crate::framebuf::WidgetFramebuf;
+use crate::grid::{Grid, GridColumn};
+use crate::smartstate::{Smartstate, SmartstateProvider};
 use crate::style::Style;
 use core::cell::UnsafeCell;
 use core::cmp::{max, min};
@@ -9,11 +12,13 @@ use embedded_graphics::geometry::Dimensions;
 use embedded_graphics::pixelcolor::PixelColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::{
-    PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StyledDrawable,
+    PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, StrokeAlignment, StyledDrawable,
 };
 use embedded_graphics::{Drawable, Pixel};
+use heapless::Vec;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub enum GuiError {
     /// The widget is too large to fit in the bounds with the current constraints
     NoSpaceLeft,
@@ -29,6 +34,10 @@ pub enum GuiError {
 
     /// The requested operation would cause the bounds to be different from the expected size
     BoundsError,
+
+    /// The widget is larger than the whole [Ui], so it can't be placed even in an empty row
+    /// (before, this was [GuiError::NoSpaceLeft])
+    WidgetTooLarge,
 }
 
 impl GuiError {
@@ -58,6 +67,7 @@ impl InternalResponse {
 }
 
 /// Response for UI interaction / space allocation and such
+#[non_exhaustive]
 pub struct Response {
     pub internal: InternalResponse,
     /// Whether the widget was clicked (as in successfully interacted with)
@@ -82,12 +92,24 @@ pub struct Response {
 
     /// Whether the widget had an error while drawing
     pub error: Option<GuiError>,
+
+    /// Whether [Interaction::Back] was given while the widget was focused
+    pub back: bool,
+
+    /// Whether the widget was long-pressed ([Interaction::LongPress]), e.g. for a secondary action
+    pub long_press: bool,
+
+    /// Whether the widget was double-tapped ([Interaction::DoubleTap])
+    pub double_click: bool,
 }
 
 // builder pattern
 impl Response {
     pub fn new(raw: InternalResponse) -> Response {
         Response {
+            back: raw.interaction == Interaction::Back,
+            long_press: matches!(raw.interaction, Interaction::LongPress(_)),
+            double_click: matches!(raw.interaction, Interaction::DoubleTap(_)),
             internal: raw,
             click: false,
             redraw: true,
@@ -126,6 +148,21 @@ impl Response {
         self
     }
 
+    pub fn set_back(mut self, back: bool) -> Self {
+        self.back = back;
+        self
+    }
+
+    pub fn set_long_pressed(mut self, long_pressed: bool) -> Self {
+        self.long_press = long_pressed;
+        self
+    }
+
+    pub fn set_double_clicked(mut self, double_clicked: bool) -> Self {
+        self.double_click = double_clicked;
+        self
+    }
+
     /// Check whether the widget was clicked (as in successfully interacted with)
     pub fn clicked(&self) -> bool {
         self.click
@@ -153,6 +190,56 @@ impl Response {
     pub fn error(&self) -> Option<GuiError> {
         self.error
     }
+
+    /// Check whether the back input ([Interaction::Back]) was given while the widget was focused
+    /// (e.g. to leave a menu)
+    pub fn back(&self) -> bool {
+        self.back
+    }
+
+    /// Check whether the widget was long-pressed (see [GestureRecognizer](crate::gesture::GestureRecognizer))
+    ///
+    /// A long press doesn't click the widget, so it can be used for a secondary action.
+    pub fn long_pressed(&self) -> bool {
+        self.long_press
+    }
+
+    /// Check whether the widget was double-tapped (see [GestureRecognizer](crate::gesture::GestureRecognizer))
+    ///
+    /// The first tap clicks the widget, the second one only double-clicks it.
+    pub fn double_clicked(&self) -> bool {
+        self.double_click
+    }
+
+    /// Combines this response with the response of another widget, e.g. to handle a group of widgets as one.
+    ///
+    /// The area becomes the bounding box of both areas (ignoring empty ones), and the first interaction is kept.
+    /// `clicked`, `down`, `changed`, `redrawn`, `back`, `long_pressed` and `double_clicked` are true if they're
+    /// true for either widget,
+    /// and the first error is kept.
+    pub fn union(mut self, other: &Response) -> Self {
+        let (a, b) = (self.internal.area, other.internal.area);
+        self.internal.area = match (a.bottom_right(), b.bottom_right()) {
+            (Some(a_end), Some(b_end)) => Rectangle::with_corners(
+                a.top_left.component_min(b.top_left),
+                a_end.component_max(b_end),
+            ),
+            (None, Some(_)) => b,
+            _ => a,
+        };
+        if self.internal.interaction == Interaction::None {
+            self.internal.interaction = other.internal.interaction;
+        }
+        self.click |= other.click;
+        self.down |= other.down;
+        self.changed |= other.changed;
+        self.redraw |= other.redraw;
+        self.back |= other.back;
+        self.long_press |= other.long_press;
+        self.double_click |= other.double_click;
+        self.error = self.error.or(other.error);
+        self
+    }
 }
 
 pub trait Widget {
@@ -160,6 +247,19 @@ pub trait Widget {
         &mut self,
         ui: &mut Ui<DRAW, COL>,
     ) -> GuiResult<Response>;
+
+    /// Returns the size the widget will allocate when drawn, without drawing it.
+    ///
+    /// `available` is the space the widget may take up. Layouts (e.g. [Ui::flex_row]) use this to
+    /// lay out widgets before they're drawn.
+    ///
+    /// Widgets that grow with the height of the row they're placed in report their minimum size.
+    ///
+    /// Returns `None` if the size isn't known before drawing, which is the default.
+    fn measure<COL: PixelColor>(&self, style: &Style<COL>, available: Size) -> Option<Size> {
+        let _ = (style, available);
+        None
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -185,6 +285,231 @@ impl Default for Align {
     }
 }
 
+/// Direction in which widgets are placed one after another
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlowDirection {
+    /// Widgets are placed in rows, which are stacked from top to bottom (the default)
+    #[default]
+    Horizontal,
+    /// Widgets are stacked from top to bottom in columns, which are placed from left to right
+    Vertical,
+}
+
+/// Horizontal direction of the layout, e.g. for right-to-left languages
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LayoutDirection {
+    /// Rows are filled from the left edge to the right (the default)
+    #[default]
+    LeftToRight,
+    /// Rows are filled from the right edge to the left, and the horizontal layout is mirrored
+    RightToLeft,
+}
+
+/// Side of a [Ui] a panel is docked to, see [Ui::panel_ui]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanelSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Extra space around a single widget, see [Ui::add_with]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Margins {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl Margins {
+    /// Creates margins with the same size on all sides.
+    pub const fn all(margin: u32) -> Self {
+        Margins {
+            left: margin,
+            top: margin,
+            right: margin,
+            bottom: margin,
+        }
+    }
+
+    /// Creates margins with `horizontal` on the left and right, and `vertical` on the top and bottom.
+    pub const fn symmetric(horizontal: u32, vertical: u32) -> Self {
+        Margins {
+            left: horizontal,
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+        }
+    }
+
+    /// Returns the total size the margins add to a widget.
+    pub const fn size(&self) -> Size {
+        Size::new(self.left + self.right, self.top + self.bottom)
+    }
+}
+
+/// Spacing overrides for a single widget, see [Ui::add_with]
+///
+/// Can be created from [Margins] if only the margins are changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WidgetSpacing {
+    /// Extra space around the widget
+    pub margins: Margins,
+    /// Inner padding of the widget, replacing the style's button and default padding (if set)
+    pub padding: Option<Size>,
+}
+
+impl WidgetSpacing {
+    /// Creates spacing overrides without any changes.
+    pub const fn new() -> Self {
+        WidgetSpacing {
+            margins: Margins::all(0),
+            padding: None,
+        }
+    }
+
+    /// Sets the extra space around the widget.
+    pub const fn margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    /// Sets the inner padding of the widget.
+    pub const fn padding(mut self, padding: Size) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+}
+
+impl From<Margins> for WidgetSpacing {
+    fn from(margins: Margins) -> Self {
+        WidgetSpacing::new().margins(margins)
+    }
+}
+
+/// The (absolute) areas of the overlays drawn in a frame, see [Ui::overlay].
+///
+/// Returned by [Ui::finish], and passed to [Ui::set_previous_overlays] in the next frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OverlayAreas {
+    areas: Vec<Rectangle, MAX_OVERLAYS>,
+}
+
+impl OverlayAreas {
+    /// Creates an empty set of overlay areas, e.g. for the first frame.
+    pub fn new() -> Self {
+        OverlayAreas { areas: Vec::new() }
+    }
+
+    /// Returns the overlay areas, in the order the overlays were drawn.
+    pub fn areas(&self) -> &[Rectangle] {
+        &self.areas
+    }
+
+    /// Returns whether the given (absolute) point is inside of any overlay.
+    pub fn contains(&self, point: Point) -> bool {
+        self.areas.iter().any(|area| area.contains(point))
+    }
+
+    /// Removes the area of an overlay that was closed, so it doesn't take precedence in the next frame anymore.
+    pub fn remove(&mut self, area: Rectangle) {
+        self.areas.retain(|other| *other != area);
+    }
+}
+
+/// The keyboard / button focus, kept across frames.
+///
+/// Focusable widgets (e.g. [Button](crate::button::Button)s) are numbered in the order they're added to the [Ui].
+/// [Interaction::FocusNext] and [Interaction::FocusPrev] move the focus between them, [Interaction::Navigate]
+/// moves it to the nearest widget on the screen in a [Direction], and [Interaction::Activate] clicks the focused widget.
+///
+/// For rotary encoders, [Interaction::Scroll] moves the focus as well. Pressing the encoder ([Interaction::Activate])
+/// on an editable widget (e.g. a [Slider](crate::slider::Slider)) enters the edit mode instead, in which
+/// [Interaction::Scroll] changes its value, until it's pressed again. Without an encoder, [Interaction::FocusNext]
+/// and [Interaction::FocusPrev] (or [Interaction::Navigate] to the right and left) change the value by one step
+/// in edit mode.
+///
+/// Passed to [Ui::set_focus_state] at the start of each frame, and returned by [Ui::focus_state] at its end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FocusState {
+    /// Index of the focused widget
+    focused: Option<usize>,
+    /// Number of focusable widgets in the last frame
+    count: usize,
+    /// (Absolute) area of the focused widget, where [Interaction::Navigate] starts from
+    area: Option<Rectangle>,
+    /// Whether the focused widget is in edit mode
+    editing: bool,
+    /// (Absolute) area of the focus ring currently on the screen, and whether it's drawn in edit mode
+    ring: Option<(Rectangle, bool)>,
+}
+
+impl FocusState {
+    /// Creates a focus state without a focused widget, e.g. for the first frame.
+    pub const fn new() -> Self {
+        FocusState {
+            focused: None,
+            count: 0,
+            area: None,
+            editing: false,
+            ring: None,
+        }
+    }
+
+    /// Returns the index of the focused widget, if any.
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// Focuses the widget with the given index, or removes the focus with `None`.
+    ///
+    /// This leaves the edit mode.
+    pub fn set_focused(&mut self, focused: Option<usize>) {
+        self.focused = focused;
+        self.editing = false;
+    }
+
+    /// Returns whether the focused widget is in edit mode (see [Interaction::Scroll]).
+    pub fn editing(&self) -> bool {
+        self.editing
+    }
+
+    /// Returns the number of focusable widgets in the last frame.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Alignment along a single axis, used to share the placement logic between both axes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AxisAlign {
+    Start,
+    Center,
+    End,
+}
+
+impl From<HorizontalAlign> for AxisAlign {
+    fn from(align: HorizontalAlign) -> Self {
+        match align {
+            HorizontalAlign::Left => AxisAlign::Start,
+            HorizontalAlign::Center => AxisAlign::Center,
+            HorizontalAlign::Right => AxisAlign::End,
+        }
+    }
+}
+
+impl From<VerticalAlign> for AxisAlign {
+    fn from(align: VerticalAlign) -> Self {
+        match align {
+            VerticalAlign::Top => AxisAlign::Start,
+            VerticalAlign::Center => AxisAlign::Center,
+            VerticalAlign::Bottom => AxisAlign::End,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Struct for managing placing of widgets in the [Ui]
 ///
@@ -192,7 +517,24 @@ impl Default for Align {
 ///
 /// ## Placement Rules
 ///
-/// - Widgets are placed in rows, from left to right, from top to bottom (for now)
+/// - Widgets are placed in rows, from top to bottom
+///   (or, with [FlowDirection::Vertical], in columns, from left to right.
+///   Rows and columns are handled the same way, just with swapped axes.)
+/// - Within a row, widgets are placed according to the [HorizontalAlign]:
+///   - [HorizontalAlign::Left]: from left to right, starting at the left edge
+///   - [HorizontalAlign::Center]: each widget is centered in the space that's still free in the row
+///   - [HorizontalAlign::Right]: from right to left, starting at the right edge
+///     (so the first widget of a row ends up as the right-most one)
+///
+///   The placer doesn't know the widgets that come later in a row. To align a row of several widgets
+///   as a whole, [Ui::horizontal] measures them first and places them from left to right, starting where
+///   the row ends up.
+/// - Within a row, widgets are placed according to the [VerticalAlign]. With [VerticalAlign::Top],
+///   the widget is given the full row height, otherwise it keeps its own height and is moved to
+///   the center / bottom of the row.
+/// - With [LayoutDirection::RightToLeft], everything is mirrored horizontally: rows start at the right edge,
+///   [HorizontalAlign::Left] places widgets from right to left, and columns are placed from right to left.
+///   Internally, the placer works left-to-right and mirrors the allocated rectangles.
 /// - Placement is deterministic and repeatable
 /// - Placement cannot happen outside of the bounds of the placer
 struct Placer {
@@ -202,14 +544,28 @@ struct Placer {
     col: u32,
     /// Position of the top left corner of the placer
     pos: Point,
-    /// Height of the current row
+    /// Height of the current row (or width of the current column)
     row_height: u32,
+    /// End of the free space in the current row (or column).
+    ///
+    /// Right-aligned (or bottom-aligned) widgets are placed from here, moving it towards the start of the row.
+    row_end: u32,
     /// Bounds of the placer
     bounds: Size,
     /// Whether to wrap to the next row if the widget doesn't fit
     wrap: bool,
-    #[allow(unused)] // TODO: use in the future
+    /// Whether widgets that can shrink are shrunk to the space that's left, instead of failing to be placed
+    shrink_to_fit: bool,
+    /// Spacing between rows (height) or columns (width) when wrapping
+    spacing: Size,
+    /// Alignment of widgets in the rows
     align: Align,
+    /// Direction in which widgets are placed
+    flow: FlowDirection,
+    /// Horizontal direction of the layout
+    direction: LayoutDirection,
+    /// Bottom right corner of the area covered by all widgets placed so far
+    extent: Size,
 }
 
 impl Placer {
@@ -220,24 +576,69 @@ impl Placer {
             col: 0,
             pos: Point::zero(),
             row_height: 0,
+            row_end: bounds.width,
             bounds,
             wrap,
+            shrink_to_fit: false,
+            spacing: Size::zero(),
             align,
+            flow: FlowDirection::Horizontal,
+            direction: LayoutDirection::LeftToRight,
+            extent: Size::zero(),
         }
     }
 
-    /// **STUB / NOT YET USEFUL**: Set the wrap setting of the placer
-    #[allow(unused)] // TODO: use in the future
+    /// Create a new placer placing widgets in the given flow direction
+    pub fn new_with_flow(bounds: Size, wrap: bool, align: Align, flow: FlowDirection) -> Self {
+        let mut placer = Placer::new(bounds, wrap, align);
+        placer.flow = flow;
+        placer.row_end = placer.row_length();
+        placer
+    }
+
+    /// Set the wrap setting of the placer
     pub fn set_wrap(&mut self, wrap: bool) {
         self.wrap = wrap;
     }
 
-    /// **STUB / NOT YET USEFUL**: Set the alignment of the placer
-    #[allow(unused)] // TODO: use in the future
+    /// Set the alignment of the placer
+    ///
+    /// This takes effect for the next placed widget.
     pub fn set_align(&mut self, align: Align) {
         self.align = align;
     }
 
+    /// Set the shrink to fit setting of the placer (see [Placer::next_shrinkable])
+    pub fn set_shrink_to_fit(&mut self, shrink_to_fit: bool) {
+        self.shrink_to_fit = shrink_to_fit;
+    }
+
+    /// Set the spacing between rows (height) or columns (width) that's applied when wrapping
+    pub fn set_spacing(&mut self, spacing: Size) {
+        self.spacing = spacing;
+    }
+
+    /// Set the horizontal direction of the layout
+    ///
+    /// This takes effect for the next placed widget.
+    pub fn set_direction(&mut self, direction: LayoutDirection) {
+        self.direction = direction;
+    }
+
+    /// Mirrors the (relative) `rect` horizontally within the bounds if the layout is right-to-left
+    fn mirror(&self, rect: Rectangle) -> Rectangle {
+        match self.direction {
+            LayoutDirection::LeftToRight => rect,
+            LayoutDirection::RightToLeft => Rectangle::new(
+                Point::new(
+                    self.bounds.width as i32 - rect.top_left.x - rect.size.width as i32,
+                    rect.top_left.y,
+                ),
+                rect.size,
+            ),
+        }
+    }
+
     /// Allocate the next widget with the given `size`, explicitly disabling wrapping for this operation
     ///
     /// ## Returns
@@ -253,37 +654,76 @@ impl Placer {
 
     /// Allocate the next widget with the given `size`.
     ///
+    /// If the widget doesn't fit into the rest of the current row and wrapping is enabled,
+    /// it's placed at the start of the next row, which begins [Placer::spacing] below the current one.
+    ///
     /// ## Returns
     ///
-    /// Returns the allocated rectangle, or an error if the widget doesn't fit
+    /// Returns the allocated rectangle, or an error if the widget doesn't fit.
+    /// The error is [GuiError::WidgetTooLarge] if the widget doesn't even fit into an empty row.
     fn next(&mut self, size: Size) -> GuiResult<Rectangle> {
+        self.place(size, false)
+    }
+
+    /// Allocate the next widget with the given `size`, like [Placer::next].
+    ///
+    /// If shrink to fit is enabled, a widget that doesn't fit is shrunk to the width that's left instead
+    /// (after wrapping, if enabled). The returned rectangle may then be narrower than `size`.
+    ///
+    /// ## Returns
+    ///
+    /// Returns the allocated rectangle, or an error if the widget doesn't fit
+    fn next_shrinkable(&mut self, size: Size) -> GuiResult<Rectangle> {
+        self.place(size, self.shrink_to_fit)
+    }
+
+    /// Allocate the next widget with the given `size`, shrinking its width if `shrink` is set
+    fn place(&mut self, size: Size, shrink: bool) -> GuiResult<Rectangle> {
+        let size = if shrink {
+            Size::new(min(size.width, self.bounds.width), size.height)
+        } else {
+            size
+        };
+
         // check that it's in bounds (size < bounds)
         if !self.check_bounds(size) {
-            return Err(GuiError::NoSpaceLeft);
+            return Err(GuiError::WidgetTooLarge);
         }
 
-        // set bounds (temporary) TODO: do this PROPERLY!
-        if let Align(HorizontalAlign::Center, _) = self.align {
-            if self.pos.x as u32 + size.width > self.bounds.width {
-                return Err(GuiError::NoSpaceLeft);
-            }
-            // Calculate the right x-coordinate to center the widget between self.pos.x and self.bounds.width
-            // (self.bounds.width + self.pos.x as u32 - size.width) / 2
-            self.pos.x = ((self.bounds.width + self.pos.x as u32 - size.width) / 2) as i32;
+        let rect = match self.flow {
+            FlowDirection::Horizontal => self.next_in_row(size, shrink)?,
+            FlowDirection::Vertical => self.next_in_column(size)?,
         };
-        let right = size.width + self.pos.x as u32;
-        let mut bottom = max(self.row_height, size.height) + self.pos.y as u32;
-        if !self.check_bounds(Size::new(right, bottom)) {
-            if self.wrap {
-                bottom = self.pos.y as u32 + max(self.row_height, size.height);
-                // check that wrap fits
-                if !self.check_bounds(Size::new(0, bottom)) {
-                    return Err(GuiError::NoSpaceLeft);
-                }
 
+        self.extent = Size::new(
+            max(
+                self.extent.width,
+                (rect.top_left.x as u32) + rect.size.width,
+            ),
+            max(
+                self.extent.height,
+                (rect.top_left.y as u32) + rect.size.height,
+            ),
+        );
+        Ok(self.mirror(rect))
+    }
+
+    /// Place the next widget in the current row, wrapping to the next row if necessary
+    fn next_in_row(&mut self, mut size: Size, shrink: bool) -> GuiResult<Rectangle> {
+        if !self.fits_in_row(size) {
+            let wrapped_top = self.pos.y as u32 + self.row_height + self.spacing.height;
+            if self.wrap && !self.row_is_empty() && wrapped_top + size.height <= self.bounds.height
+            {
                 // perform wrap
-                self.new_row(size.height); // TODO: better / proper wrap impl
-            } else {
+                self.new_row(0);
+                self.pos.y += self.spacing.height as i32;
+            }
+
+            if shrink {
+                size.width = min(size.width, self.row_end.saturating_sub(self.pos.x as u32));
+            }
+
+            if !self.fits_in_row(size) {
                 return Err(GuiError::NoSpaceLeft);
             }
         }
@@ -291,16 +731,155 @@ impl Placer {
         // set new col height (expand if necessary)
         self.row_height = max(self.row_height, size.height);
 
-        // set new position
-        let item_pos = self.pos;
-        self.pos = Point::new(right as i32, self.pos.y);
+        let x = Self::place_on_line(
+            &mut self.pos.x,
+            &mut self.row_end,
+            size.width,
+            self.align.0.into(),
+        );
+        let (y, height) = Self::align_across_line(
+            self.pos.y,
+            self.row_height,
+            size.height,
+            self.align.1.into(),
+        );
+
+        Ok(Rectangle::new(
+            Point::new(x, y),
+            Size::new(size.width, height),
+        ))
+    }
+
+    /// Place the next widget in the current column, wrapping to the next column if necessary
+    fn next_in_column(&mut self, size: Size) -> GuiResult<Rectangle> {
+        if !self.fits_in_row(size) {
+            let wrapped_left = self.pos.x as u32 + self.row_height + self.spacing.width;
+            if self.wrap && !self.row_is_empty() && wrapped_left + size.width <= self.bounds.width {
+                // perform wrap
+                self.new_row(0);
+                self.pos.x += self.spacing.width as i32;
+            }
+
+            if !self.fits_in_row(size) {
+                return Err(GuiError::NoSpaceLeft);
+            }
+        }
+
+        // set new column width (expand if necessary)
+        self.row_height = max(self.row_height, size.width);
+
+        let y = Self::place_on_line(
+            &mut self.pos.y,
+            &mut self.row_end,
+            size.height,
+            self.align.1.into(),
+        );
+        let (x, width) =
+            Self::align_across_line(self.pos.x, self.row_height, size.width, self.align.0.into());
 
         Ok(Rectangle::new(
-            item_pos,
-            Size::new(size.width, self.row_height),
+            Point::new(x, y),
+            Size::new(width, size.height),
         ))
     }
 
+    /// Returns whether a widget of the given `size` fits into the rest of the current row (or column)
+    fn fits_in_row(&self, size: Size) -> bool {
+        match self.flow {
+            FlowDirection::Horizontal => {
+                self.pos.x as u32 + size.width <= self.row_end
+                    && self.pos.y as u32 + max(self.row_height, size.height) <= self.bounds.height
+            }
+            FlowDirection::Vertical => {
+                self.pos.y as u32 + size.height <= self.row_end
+                    && self.pos.x as u32 + max(self.row_height, size.width) <= self.bounds.width
+            }
+        }
+    }
+
+    /// Returns whether no widget has been placed in the current row (or column) yet
+    fn row_is_empty(&self) -> bool {
+        let start = match self.flow {
+            FlowDirection::Horizontal => self.pos.x,
+            FlowDirection::Vertical => self.pos.y,
+        };
+        start == 0 && self.row_end == self.row_length()
+    }
+
+    /// Place an item of the given `length` along the current row (or column),
+    /// moving the start position `pos` or the free space end `end` past it.
+    ///
+    /// ## Returns
+    ///
+    /// The start coordinate of the item
+    fn place_on_line(pos: &mut i32, end: &mut u32, length: u32, align: AxisAlign) -> i32 {
+        let free = end.saturating_sub(*pos as u32);
+        match align {
+            AxisAlign::Start => {
+                let start = *pos;
+                *pos += length as i32;
+                start
+            }
+            AxisAlign::Center => {
+                let start = *pos + (free.saturating_sub(length) / 2) as i32;
+                *pos = start + length as i32;
+                start
+            }
+            AxisAlign::End => {
+                *end = end.saturating_sub(length);
+                *end as i32
+            }
+        }
+    }
+
+    /// Align an item of the given `length` across a row (or column) of the given `thickness`,
+    /// starting at `start`.
+    ///
+    /// Items aligned to the start get the full thickness of the row.
+    ///
+    /// ## Returns
+    ///
+    /// The start coordinate and length of the item
+    fn align_across_line(start: i32, thickness: u32, length: u32, align: AxisAlign) -> (i32, u32) {
+        match align {
+            AxisAlign::Start => (start, thickness),
+            AxisAlign::Center => (start + ((thickness - length) / 2) as i32, length),
+            AxisAlign::End => (start + (thickness - length) as i32, length),
+        }
+    }
+
+    /// Add empty space of the given `size` after the last placed widget.
+    ///
+    /// Unlike [Placer::next], the space itself isn't aligned, it just moves the position in the
+    /// direction the widgets are placed in (e.g. to the left for [HorizontalAlign::Right]).
+    /// Spacing that doesn't fit into the current row is silently dropped.
+    fn space(&mut self, size: Size) {
+        match self.flow {
+            FlowDirection::Horizontal => {
+                let free = self.row_end.saturating_sub(self.pos.x as u32);
+                let width = min(size.width, free);
+                match self.align.0 {
+                    HorizontalAlign::Left | HorizontalAlign::Center => self.pos.x += width as i32,
+                    HorizontalAlign::Right => self.row_end -= width,
+                }
+                if self.pos.y as u32 + size.height <= self.bounds.height {
+                    self.expand_row_height(size.height);
+                }
+            }
+            FlowDirection::Vertical => {
+                let free = self.row_end.saturating_sub(self.pos.y as u32);
+                let height = min(size.height, free);
+                match self.align.1 {
+                    VerticalAlign::Top | VerticalAlign::Center => self.pos.y += height as i32,
+                    VerticalAlign::Bottom => self.row_end -= height,
+                }
+                if self.pos.x as u32 + size.width <= self.bounds.width {
+                    self.expand_row_height(size.width);
+                }
+            }
+        }
+    }
+
     #[allow(unused)]
     /// Returns the full size of the current row, which is the full width of the bounds and the current row height.
     fn row_size(&self) -> Size {
@@ -308,20 +887,52 @@ impl Placer {
     }
 
     /// Returns the remaining available space within the bounds of the [Placer] for placing widgets.
-    /// The remaining space hearby is a rectangle from the current plcer X and Y position to the bottom right corner of the bounds.
+    /// The remaining space hearby is a rectangle from the current plcer X and Y position to the bottom right corner of the bounds
+    /// (or the left-most right-aligned widget in the current row, if there is one).
     fn space_available(&self) -> Size {
-        Size::new(
-            self.bounds.width - self.pos.x as u32,
-            self.bounds.height - self.pos.y as u32,
-        )
+        match self.flow {
+            FlowDirection::Horizontal => Size::new(
+                self.row_end.saturating_sub(self.pos.x as u32),
+                self.bounds.height - self.pos.y as u32,
+            ),
+            FlowDirection::Vertical => Size::new(
+                self.bounds.width - self.pos.x as u32,
+                self.row_end.saturating_sub(self.pos.y as u32),
+            ),
+        }
+    }
+
+    /// Returns the (relative) free area, starting at the current position (see [Placer::space_available])
+    fn free_rect(&self) -> Rectangle {
+        self.mirror(Rectangle::new(self.pos, self.space_available()))
+    }
+
+    /// Returns the size covered by all widgets placed so far, measured from the top left corner of the bounds
+    /// (or the top right corner, if the layout is right-to-left).
+    fn used_size(&self) -> Size {
+        self.extent
+    }
+
+    /// Returns the full length of a row (or column) in the flow direction
+    fn row_length(&self) -> u32 {
+        match self.flow {
+            FlowDirection::Horizontal => self.bounds.width,
+            FlowDirection::Vertical => self.bounds.height,
+        }
     }
 
     /// Advances to the next row, setting the initial row height to the provided `height` parameter.
+    ///
+    /// With [FlowDirection::Vertical], this advances to the next column instead, with `height` being the initial column width.
     fn new_row(&mut self, height: u32) {
         self.row += 1;
         self.col = 0;
-        self.pos = Point::new(0, self.pos.y + self.row_height as i32);
+        self.pos = match self.flow {
+            FlowDirection::Horizontal => Point::new(0, self.pos.y + self.row_height as i32),
+            FlowDirection::Vertical => Point::new(self.pos.x + self.row_height as i32, 0),
+        };
         self.row_height = height;
+        self.row_end = self.row_length();
     }
 
     /// Returns the current row height.
@@ -334,12 +945,59 @@ impl Placer {
         self.row_height = max(self.row_height, height);
     }
 
+    /// Shrinks the bounds by `start` at the top left and by `end` at the bottom right corner,
+    /// e.g. to make room for a docked panel.
+    ///
+    /// The position moves along with the top left corner (top right, if the layout is right-to-left).
+    /// If that corner moves past the start of the current row (or column), placement continues
+    /// in a fresh row at the new corner.
+    fn shrink(&mut self, start: Size, end: Size) {
+        // the start of the rows is on the right
+        let (start, end) = match self.direction {
+            LayoutDirection::LeftToRight => (start, end),
+            LayoutDirection::RightToLeft => (
+                Size::new(end.width, start.height),
+                Size::new(start.width, end.height),
+            ),
+        };
+        self.bounds = self.bounds.saturating_sub(start + end);
+        self.extent = self.extent.saturating_sub(start);
+
+        let (row_start, row_shift, across_start, across_shift) = match self.flow {
+            FlowDirection::Horizontal => (self.pos.x, start.width, self.pos.y, start.height),
+            FlowDirection::Vertical => (self.pos.y, start.height, self.pos.x, start.width),
+        };
+
+        if across_shift as i32 > across_start {
+            self.pos = Point::zero();
+            self.row_height = 0;
+            self.row_end = self.row_length();
+            return;
+        }
+
+        let (row_start, across_start) = (
+            max(row_start - row_shift as i32, 0),
+            across_start - across_shift as i32,
+        );
+        self.pos = match self.flow {
+            FlowDirection::Horizontal => Point::new(row_start, across_start),
+            FlowDirection::Vertical => Point::new(across_start, row_start),
+        };
+        self.row_end = min(self.row_end.saturating_sub(row_shift), self.row_length());
+    }
+
     /// Check whether a size is in bounds of the widget (<= widget_size)
     fn check_bounds(&self, pos: Size) -> bool {
         pos.width <= self.bounds.width && pos.height <= self.bounds.height
     }
 }
 
+/// Maximum number of areas a [Painter] can exclude from drawing (see [Painter::exclude])
+const MAX_EXCLUDED_AREAS: usize = 8;
+
+/// Maximum number of overlays per frame (see [Ui::overlay])
+const MAX_OVERLAYS: usize = 8;
+
 /// Struct that manages drawing to a [DrawTarget], with optional [WidgetFramebuf] for more efficient drawing.
 ///
 ///
@@ -348,6 +1006,20 @@ struct Painter<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> {
     target: &'a mut DRAW,
     buffer_raw: Option<UnsafeCell<&'a mut [COL]>>,
     framebuf: Option<WidgetFramebuf<'a, COL>>,
+    /// Area outside of which nothing is drawn (if set)
+    clip: Option<Rectangle>,
+    /// Rotation of the UI on the [DrawTarget]
+    rotation: Rotation,
+    /// Areas inside of which nothing is drawn, e.g. because a window is on top of them
+    excluded: Vec<Rectangle, MAX_EXCLUDED_AREAS>,
+    /// Areas of the overlays of the previous frame, followed by the ones of this frame (see [Ui::overlay])
+    overlays: Vec<Rectangle, { 2 * MAX_OVERLAYS }>,
+    /// How many of the overlay areas are from the previous frame
+    previous_overlays: usize,
+    /// Ids of the overlays queued in this frame, in the order of their areas (see [Ui::overlay])
+    queued_overlays: Vec<usize, MAX_OVERLAYS>,
+    /// Whether this painter draws an overlay, so it isn't excluded from the overlay areas
+    in_overlay: bool,
 }
 
 impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW> {
@@ -357,6 +1029,67 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
             target,
             buffer_raw: None,
             framebuf: None,
+            clip: None,
+            rotation: Rotation::Deg0,
+            excluded: Vec::new(),
+            overlays: Vec::new(),
+            previous_overlays: 0,
+            queued_overlays: Vec::new(),
+            in_overlay: false,
+        }
+    }
+
+    /// Returns the overlay areas this painter has to stay out of
+    fn overlays(&self) -> &[Rectangle] {
+        if self.in_overlay {
+            &[]
+        } else {
+            &self.overlays
+        }
+    }
+
+    /// Excludes the given area from all further drawing (in addition to the clipping area).
+    ///
+    /// ## Returns
+    ///
+    /// An error if more than [MAX_EXCLUDED_AREAS] areas are excluded.
+    fn exclude(&mut self, area: Rectangle) -> GuiResult<()> {
+        self.excluded
+            .push(area)
+            .map_err(|_| GuiError::draw_error("Too many excluded areas"))
+    }
+
+    /// Returns whether drawing to the given point is visible, i.e. not clipped, excluded or covered by an overlay.
+    fn is_visible(&self, point: Point) -> bool {
+        self.clip.map_or(true, |clip| clip.contains(point))
+            && !self
+                .excluded
+                .iter()
+                .chain(self.overlays())
+                .any(|area| area.contains(point))
+    }
+
+    /// Maps a point on the [DrawTarget] (e.g. from a touch screen) to the (rotated) coordinates of the UI.
+    fn to_logical(&self, point: Point) -> Point {
+        self.rotation.to_logical(point, self.target.bounding_box())
+    }
+
+    /// Restricts all further drawing to the given area (intersected with the current clipping area, if any).
+    fn clip_to(&mut self, area: Rectangle) {
+        self.clip = Some(match self.clip {
+            Some(clip) => clip.intersection(&area),
+            None => area,
+        });
+    }
+
+    /// Returns the [DrawTarget] to draw to, applying the clipping area.
+    fn output(&mut self) -> PainterOutput<'_, DRAW> {
+        PainterOutput {
+            target: self.target,
+            clip: self.clip,
+            rotation: self.rotation,
+            excluded: &self.excluded,
+            overlays: if self.in_overlay { &[] } else { &self.overlays },
         }
     }
 
@@ -411,7 +1144,14 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
     /// If not, the drawing operations are performed directly on the [DrawTarget].
     fn finalize(&mut self) -> GuiResult<()> {
         if let Some(buf) = &mut self.framebuf {
-            buf.draw(self.target)
+            let mut output = PainterOutput {
+                target: self.target,
+                clip: self.clip,
+                rotation: self.rotation,
+                excluded: &self.excluded,
+                overlays: if self.in_overlay { &[] } else { &self.overlays },
+            };
+            buf.draw(&mut output)
                 .map_err(|_| GuiError::draw_error("Failed to draw framebuf"))?;
             self.framebuf = None;
         }
@@ -431,7 +1171,7 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
             item.draw(buffer)
                 .ok() /* cannot fail */;
         } else {
-            item.draw(self.target)
+            item.draw(&mut self.output())
                 .map_err(|_| GuiError::draw_error("Failed to draw item"))?;
         }
         Ok(())
@@ -444,17 +1184,25 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
     /// be reflected in the main [DrawTarget].
     ///
     /// If the main [Painter] instance has a raw buffer set, the sub-painter will inherit that buffer.
+    /// The sub-painter also inherits the clipping area, the excluded areas, the overlay areas and the rotation.
     ///
     /// ## Panics
     ///
     /// Panics if the main [Painter] instance is currently using its framebuffer, as sub-painters cannot be created when the framebuffer is in use.
     /// Make sure to call [Painter::finalize()] before creating a sub-painter to prevent this.
-    fn with_subpainter<'b, F>(&'b mut self, f: F) -> GuiResult<()>
+    fn with_subpainter<'b, F, R>(&'b mut self, f: F) -> GuiResult<R>
     where
-        F: FnOnce(Painter<'b, COL, DRAW>) -> GuiResult<()>,
+        F: FnOnce(Painter<'b, COL, DRAW>) -> GuiResult<R>,
     {
         let target: &'b mut DRAW = self.target;
         let mut subpainter = Painter::new(target);
+        subpainter.clip = self.clip;
+        subpainter.rotation = self.rotation;
+        subpainter.excluded = self.excluded.clone();
+        subpainter.overlays = self.overlays.clone();
+        subpainter.previous_overlays = self.previous_overlays;
+        subpainter.queued_overlays = self.queued_overlays.clone();
+        subpainter.in_overlay = self.in_overlay;
 
         if self.framebuf.is_some() {
             panic!("Cannot create subpainter when framebuf is in use!");
@@ -463,8 +1211,7 @@ impl<'a, COL: PixelColor, DRAW: DrawTarget<Color = COL>> Painter<'a, COL, DRAW>
         if let Some(buf) = &mut self.buffer_raw {
             subpainter.set_buffer(unsafe { *buf.get() });
         }
-        (f)(subpainter)?;
-        Ok(())
+        (f)(subpainter)
     }
 }
 
@@ -473,7 +1220,7 @@ impl<COL: PixelColor, DRAW: DrawTarget<Color = COL, Error = ERR>, ERR> Dimension
     for Painter<'_, COL, DRAW>
 {
     fn bounding_box(&self) -> Rectangle {
-        self.target.bounding_box()
+        self.rotation.logical_bounds(self.target.bounding_box())
     }
 }
 
@@ -489,38 +1236,275 @@ impl<COL: PixelColor, DRAW: DrawTarget<Color = COL, Error = ERR>, ERR> DrawTarge
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        self.target.draw_iter(pixels)
+        self.output().draw_iter(pixels)
     }
 }
 
-/// Interaction with the UI
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub enum Interaction {
-    /// A click event (mouse, touch, etc. down)
-    Click(Point),
-    /// A drag event (mouse, touch, etc. move while clicked)
+/// [DrawTarget] that the [Painter] draws to, discarding everything outside of the clipping area
+/// (or inside of an excluded or overlay area) and rotating everything else onto the actual [DrawTarget].
+///
+/// The clipping, excluded and overlay areas are in the (rotated) coordinates of the UI.
+struct PainterOutput<'t, DRAW> {
+    target: &'t mut DRAW,
+    clip: Option<Rectangle>,
+    rotation: Rotation,
+    excluded: &'t [Rectangle],
+    overlays: &'t [Rectangle],
+}
+
+impl<DRAW> PainterOutput<'_, DRAW> {
+    /// Returns whether any excluded or overlay area overlaps the given area
+    fn overlaps_excluded(&self, area: &Rectangle) -> bool {
+        self.excluded
+            .iter()
+            .chain(self.overlays)
+            .any(|excluded| !excluded.intersection(area).is_zero_sized())
+    }
+}
+
+impl<DRAW: Dimensions> Dimensions for PainterOutput<'_, DRAW> {
+    fn bounding_box(&self) -> Rectangle {
+        self.rotation.logical_bounds(self.target.bounding_box())
+    }
+}
+
+impl<COL: PixelColor, DRAW: DrawTarget<Color = COL>> DrawTarget for PainterOutput<'_, DRAW> {
+    type Color = COL;
+    type Error = DRAW::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (clip, excluded, overlays) = (self.clip, self.excluded, self.overlays);
+        let clipped = pixels.into_iter().filter(move |Pixel(pt, _)| {
+            clip.map_or(true, |clip| clip.contains(*pt))
+                && !excluded
+                    .iter()
+                    .chain(overlays)
+                    .any(|area| area.contains(*pt))
+        });
+        match self.rotation {
+            Rotation::Deg0 => self.target.draw_iter(clipped),
+            rotation => {
+                let physical = self.target.bounding_box();
+                self.target.draw_iter(
+                    clipped
+                        .map(|Pixel(pt, color)| Pixel(rotation.to_physical(pt, physical), color)),
+                )
+            }
+        }
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clipped = self
+            .clip
+            .is_some_and(|clip| clip.intersection(area) != *area);
+        // rotated rows aren't contiguous on the target anymore
+        if clipped || self.rotation != Rotation::Deg0 || self.overlaps_excluded(area) {
+            self.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .map(|(pt, color)| Pixel(pt, color)),
+            )
+        } else {
+            self.target.fill_contiguous(area, colors)
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = match self.clip {
+            Some(clip) => clip.intersection(area),
+            None => *area,
+        };
+        if self.overlaps_excluded(&area) {
+            return self.draw_iter(area.points().map(|pt| Pixel(pt, color)));
+        }
+        let physical = self.target.bounding_box();
+        self.target
+            .fill_solid(&self.rotation.rect_to_physical(area, physical), color)
+    }
+}
+
+/// Rotation of the UI on the display, e.g. for displays mounted in portrait orientation
+///
+/// The UI is rotated clockwise by the given angle. For [Rotation::Deg90] and [Rotation::Deg270],
+/// the width and height of the UI are swapped compared to the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rotation {
+    /// No rotation (the default)
+    #[default]
+    Deg0,
+    /// Rotated by 90° clockwise, so the top of the UI is at the right edge of the display
+    Deg90,
+    /// Rotated by 180°, so the UI is upside down
+    Deg180,
+    /// Rotated by 270° clockwise, so the top of the UI is at the left edge of the display
+    Deg270,
+}
+
+impl Rotation {
+    /// Returns the bounds of the UI on a display with the given `physical` bounds.
+    fn logical_bounds(self, physical: Rectangle) -> Rectangle {
+        match self {
+            Rotation::Deg0 | Rotation::Deg180 => physical,
+            Rotation::Deg90 | Rotation::Deg270 => Rectangle::new(
+                physical.top_left,
+                Size::new(physical.size.height, physical.size.width),
+            ),
+        }
+    }
+
+    /// Maps a point of the UI to the display with the given `physical` bounds.
+    fn to_physical(self, point: Point, physical: Rectangle) -> Point {
+        let p = point - physical.top_left;
+        let (w, h) = (
+            physical.size.width as i32 - 1,
+            physical.size.height as i32 - 1,
+        );
+        physical.top_left
+            + match self {
+                Rotation::Deg0 => p,
+                Rotation::Deg90 => Point::new(w - p.y, p.x),
+                Rotation::Deg180 => Point::new(w - p.x, h - p.y),
+                Rotation::Deg270 => Point::new(p.y, h - p.x),
+            }
+    }
+
+    /// Maps a point of the display with the given `physical` bounds to the UI (the inverse of [Rotation::to_physical]).
+    fn to_logical(self, point: Point, physical: Rectangle) -> Point {
+        let p = point - physical.top_left;
+        let (w, h) = (
+            physical.size.width as i32 - 1,
+            physical.size.height as i32 - 1,
+        );
+        physical.top_left
+            + match self {
+                Rotation::Deg0 => p,
+                Rotation::Deg90 => Point::new(p.y, w - p.x),
+                Rotation::Deg180 => Point::new(w - p.x, h - p.y),
+                Rotation::Deg270 => Point::new(h - p.y, p.x),
+            }
+    }
+
+    /// Maps a rectangle of the UI to the display with the given `physical` bounds.
+    fn rect_to_physical(self, rect: Rectangle, physical: Rectangle) -> Rectangle {
+        match (self, rect.bottom_right()) {
+            (Rotation::Deg0, _) | (_, None) => rect,
+            (_, Some(bottom_right)) => Rectangle::with_corners(
+                self.to_physical(rect.top_left, physical),
+                self.to_physical(bottom_right, physical),
+            ),
+        }
+    }
+}
+
+/// Direction on the screen, e.g. of a D-pad button (see [Interaction::Navigate]) or a swipe (see [Interaction::Swipe])
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Returns the direction the `offset` mostly points in (horizontal, if both axes are equal)
+    pub(crate) fn from_offset(offset: Point) -> Self {
+        match offset {
+            Point { x, y } if y.abs() > x.abs() && y < 0 => Direction::Up,
+            Point { x, y } if y.abs() > x.abs() => Direction::Down,
+            Point { x, .. } if x < 0 => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+
+    /// Returns a unit offset pointing in the direction
+    fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+        }
+    }
+}
+
+/// Interaction with the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[non_exhaustive]
+pub enum Interaction {
+    /// A click event (mouse, touch, etc. down)
+    Click(Point),
+    /// A drag event (mouse, touch, etc. move while clicked)
     Drag(Point),
     /// A release event (mouse, touch, etc. up)
     Release(Point),
     /// A hover event (mouse, touch, etc. move while not clicked).
     /// Generally not applicable to touch screens.
     Hover(Point),
+    /// Moves the focus to the next focusable widget (e.g. a "down" button), see [FocusState].
+    /// In edit mode, increases the value of the focused widget by one step instead.
+    FocusNext,
+    /// Moves the focus to the previous focusable widget (e.g. an "up" button), see [FocusState].
+    /// In edit mode, decreases the value of the focused widget by one step instead.
+    FocusPrev,
+    /// Moves the focus to the nearest focusable widget in the direction (e.g. a D-pad), see [FocusState].
+    /// At the edges of the screen, the focus wraps around to the opposite side.
+    Navigate(Direction),
+    /// Turns of a rotary encoder (positive for clockwise), see [FocusState].
+    /// Moves the focus by the given number of widgets, or changes the value of the focused widget in edit mode.
+    Scroll(i32),
+    /// Activates the focused widget (e.g. an "enter" button), as if it was clicked.
+    /// On editable widgets, enters or leaves the edit mode instead.
+    Activate,
+    /// The back input (e.g. a "back" button), reported by the [Response] of the focused widget
+    Back,
+    /// A touch held in place, see [GestureRecognizer](crate::gesture::GestureRecognizer)
+    LongPress(Point),
+    /// A second tap shortly after the first one, see [GestureRecognizer](crate::gesture::GestureRecognizer)
+    DoubleTap(Point),
+    /// A fast movement with the direction and the velocity (in pixels per second),
+    /// see [GestureRecognizer](crate::gesture::GestureRecognizer)
+    Swipe(Direction, u32),
     /// No interaction
     #[default]
     None,
 }
 
 impl Interaction {
+    /// Applies `f` to the point associated with the interaction, if any (and to the direction of a swipe).
+    fn map_point(self, f: impl Fn(Point) -> Point) -> Self {
+        match self {
+            Interaction::Click(p) => Interaction::Click(f(p)),
+            Interaction::Drag(p) => Interaction::Drag(f(p)),
+            Interaction::Release(p) => Interaction::Release(f(p)),
+            Interaction::Hover(p) => Interaction::Hover(f(p)),
+            Interaction::LongPress(p) => Interaction::LongPress(f(p)),
+            Interaction::DoubleTap(p) => Interaction::DoubleTap(f(p)),
+            Interaction::Swipe(direction, velocity) => Interaction::Swipe(
+                Direction::from_offset(f(direction.offset()) - f(Point::zero())),
+                velocity,
+            ),
+            other => other,
+        }
+    }
+
     /// Gets the point associated with the current interaction, if any.
     ///
     /// This method returns the point associated with the current interaction, such as the click, drag, release, or hover point. If the interaction is [Interaction::None], this method returns [None`.
-    fn get_point(&self) -> Option<Point> {
+    pub(crate) fn get_point(&self) -> Option<Point> {
         match self {
             Interaction::Click(p) => Some(*p),
             Interaction::Drag(p) => Some(*p),
             Interaction::Release(p) => Some(*p),
             Interaction::Hover(p) => Some(*p),
-            Interaction::None => None,
+            Interaction::LongPress(p) => Some(*p),
+            Interaction::DoubleTap(p) => Some(*p),
+            _ => None,
         }
     }
 }
@@ -565,6 +1549,18 @@ where
     /// Whether the UI was background-cleared this frame
     cleared: bool,
     debug_color: Option<COL>,
+    /// Margins to add to the next allocation (see [Ui::add_with])
+    margins: Option<Margins>,
+    /// Combined response of the widgets added in [Ui::horizontal] (if this is its [Ui])
+    combined: Option<Response>,
+    /// In the measure pass of [Ui::horizontal], whether all widgets could be measured so far
+    measuring: Option<bool>,
+    /// Keyboard / button focus (see [Ui::set_focus_state])
+    focus: FocusState,
+    /// Index of the next focusable widget added this frame
+    focus_index: usize,
+    /// Score, index and area of the nearest focusable widget for [Interaction::Navigate] so far
+    focus_target: Option<((bool, u32), usize, Rectangle)>,
 }
 
 // -- Getter methods for [Ui] --
@@ -663,6 +1659,44 @@ where
     /// let ui = Ui::new(&mut display, bounds, medsize_rgb565_style());
     /// ```
     pub fn new(drawable: &'a mut DRAW, bounds: Rectangle, style: Style<COL>) -> Self {
+        Ui::new_rotated(drawable, bounds, style, Rotation::Deg0)
+    }
+
+    /// Creates a new [Ui] instance with the given drawable, bounds and style, rotated on the drawable.
+    ///
+    /// The [Rotation] is applied to everything that's drawn, and its inverse to the points of
+    /// interactions passed to [Ui::interact]. This way, the same UI code works on displays mounted in
+    /// any orientation. The `bounds` are in the coordinates of the rotated UI, see [Ui::new_fullscreen_rotated]
+    /// for using the whole drawable.
+    ///
+    /// ## Returns
+    ///
+    /// A new instance of [Ui].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::geometry::Size;
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// use embedded_graphics::primitives::Rectangle;
+    /// use embedded_graphics::geometry::Point;
+    /// use kolibri_embedded_gui::ui::Rotation;
+    /// // a 240x320 portrait UI on the landscape display
+    /// let bounds = Rectangle::new(Point::zero(), Size::new(240, 320));
+    /// let ui = Ui::new_rotated(&mut display, bounds, medsize_rgb565_style(), Rotation::Deg90);
+    /// ```
+    pub fn new_rotated(
+        drawable: &'a mut DRAW,
+        bounds: Rectangle,
+        style: Style<COL>,
+        rotation: Rotation,
+    ) -> Self {
         // set bounds to internal bounds (apply padding)
         let bounds = Rectangle::new(
             bounds.top_left.add(Point::new(
@@ -681,14 +1715,23 @@ where
             Align(HorizontalAlign::Left, VerticalAlign::Top),
         );
 
+        let mut painter = Painter::new(drawable);
+        painter.rotation = rotation;
+
         Self {
             bounds,
-            painter: Painter::new(drawable),
+            painter,
             style,
             placer,
             interact: Interaction::None,
             cleared: false,
             debug_color: None,
+            margins: None,
+            combined: None,
+            measuring: None,
+            focus: FocusState::new(),
+            focus_index: 0,
+            focus_target: None,
         }
     }
 
@@ -722,10 +1765,51 @@ where
         Ui::new(drawable, bounds, style)
     }
 
+    /// Creates a new fullscreen [Ui] instance using the entire drawable, rotated by `rotation`.
+    ///
+    /// For [Rotation::Deg90] and [Rotation::Deg270], the width and height of the [Ui] are swapped
+    /// compared to the drawable. See [Ui::new_rotated] for details.
+    ///
+    /// ## Returns
+    ///
+    /// A new fullscreen instance of [Ui].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::geometry::Size;
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// use kolibri_embedded_gui::ui::Rotation;
+    /// // the display is mounted upside down
+    /// let ui = Ui::new_fullscreen_rotated(&mut display, medsize_rgb565_style(), Rotation::Deg180);
+    /// ```
+    pub fn new_fullscreen_rotated(
+        drawable: &'a mut DRAW,
+        style: Style<COL>,
+        rotation: Rotation,
+    ) -> Self {
+        let bounds = rotation.logical_bounds(drawable.bounding_box());
+        Ui::new_rotated(drawable, bounds, style, rotation)
+    }
+
+    /// Returns the [Rotation] of the [Ui] on its drawable.
+    pub fn rotation(&self) -> Rotation {
+        self.painter.rotation
+    }
+
     /// Sets the current interaction for the [Ui].
     ///
     /// This interaction is used to update the state of widgets.
     ///
+    /// The point of the interaction is in the coordinates of the drawable (e.g. straight from the touch screen).
+    /// If the [Ui] is rotated (see [Ui::new_rotated]), it is mapped to the coordinates of the rotated [Ui].
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -744,7 +1828,161 @@ where
     /// ui.interact(Interaction::Click(Point::new(10, 10)));
     /// ```
     pub fn interact(&mut self, interaction: Interaction) {
-        self.interact = interaction;
+        self.interact = interaction.map_point(|point| self.painter.to_logical(point));
+    }
+
+    /// Sets the keyboard / button focus, as returned by [Ui::focus_state] in the previous frame.
+    ///
+    /// Without a focus state, the focus interactions (e.g. [Interaction::FocusNext] or [Interaction::Activate])
+    /// have no effect. The focused widget is drawn with a focus ring in the style's highlight border color
+    /// (or its secondary color in edit mode), just outside of its area.
+    ///
+    /// This should be called right after creating the [Ui], before adding any widgets. It doesn't matter
+    /// whether it's called before or after [Ui::interact], as the focus is only moved once the first
+    /// focusable widget is added (or at the latest in [Ui::focus_state]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::button::Button;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// let mut focus = FocusState::new();
+    /// loop {
+    ///     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    ///     ui.set_focus_state(focus);
+    ///     // e.g. from the "down" button
+    ///     ui.interact(Interaction::FocusNext);
+    ///
+    ///     if ui.add(Button::new("Start")).clicked() {
+    ///         // clicked, or activated while focused
+    ///     }
+    ///     ui.add(Button::new("Stop"));
+    ///
+    ///     focus = ui.focus_state();
+    ///     # break;
+    /// }
+    /// ```
+    pub fn set_focus_state(&mut self, focus: FocusState) {
+        self.focus = focus;
+        self.focus_index = 0;
+    }
+
+    /// Returns the keyboard / button focus, to be passed to [Ui::set_focus_state] in the next frame.
+    ///
+    /// This should be called after all widgets were added. [Interaction::Navigate] moves the focus here,
+    /// as the nearest widget in the direction is only known once all widgets were added.
+    /// If the focused widget wasn't added this frame, its focus ring is removed.
+    ///
+    /// ## Returns
+    ///
+    /// The [FocusState] at the end of this frame.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// let focus = ui.focus_state();
+    /// if let Some(index) = focus.focused() {
+    ///     println!("Widget {} of {} is focused", index, focus.count());
+    /// }
+    /// ```
+    pub fn focus_state(&mut self) -> FocusState {
+        self.focus.count = self.focus_index;
+        // with no focusable widgets in the last frame, the focus couldn't be moved yet
+        self.navigate_focus();
+
+        // the nearest widget in the direction is only known once all widgets were added
+        if let Interaction::Navigate(_) = self.interact {
+            match self.focus_target.take() {
+                Some((_, index, area)) => {
+                    self.focus.set_focused(Some(index));
+                    self.focus.area = Some(area);
+                    self.show_focus_ring(area).ok();
+                }
+                None if self.focus.focused.is_none() && self.focus_index > 0 => {
+                    self.focus.focused = Some(0);
+                }
+                None => {}
+            }
+            self.interact = Interaction::None;
+        }
+
+        let shown = self.focus.focused.is_some_and(|i| i < self.focus_index);
+        if !shown {
+            self.focus.editing = false;
+            if let Some((ring, _)) = self.focus.ring.take() {
+                self.draw_focus_ring(ring, self.style.background_color).ok();
+            }
+        }
+        self.focus
+    }
+
+    /// Moves the focus for [Interaction::FocusNext], [Interaction::FocusPrev] and [Interaction::Scroll], which are consumed
+    /// (unless there were no focusable widgets in the last frame).
+    ///
+    /// This is done lazily, once both the focus state and the interaction are known: before the first focusable
+    /// widget or child [Ui], and in [Ui::focus_state]. As the interaction is consumed, calling it again has no effect.
+    fn navigate_focus(&mut self) {
+        let count = self.focus.count;
+        if count == 0 {
+            return;
+        }
+        // in edit mode, the buttons change the value of the focused widget by one step instead
+        if self.focus.editing {
+            let forward = match self.placer.direction {
+                LayoutDirection::LeftToRight => Direction::Right,
+                LayoutDirection::RightToLeft => Direction::Left,
+            };
+            match self.interact {
+                Interaction::FocusNext => self.interact = Interaction::Scroll(1),
+                Interaction::FocusPrev => self.interact = Interaction::Scroll(-1),
+                Interaction::Navigate(direction @ (Direction::Left | Direction::Right)) => {
+                    let step = if direction == forward { 1 } else { -1 };
+                    self.interact = Interaction::Scroll(step);
+                }
+                _ => {}
+            }
+        }
+        let focused = match (self.interact, self.focus.focused) {
+            (Interaction::FocusNext, Some(i)) => (i + 1) % count,
+            (Interaction::FocusNext, None) => 0,
+            (Interaction::FocusPrev, Some(i)) => (min(i, count) + count - 1) % count,
+            (Interaction::FocusPrev, None) => count - 1,
+            // in edit mode, the focused widget handles the scrolling
+            (Interaction::Scroll(delta), focused) if delta != 0 && !self.focus.editing => {
+                let start = match focused {
+                    Some(i) => min(i, count - 1) as i64,
+                    None if delta > 0 => -1,
+                    None => count as i64,
+                };
+                (start + delta as i64).rem_euclid(count as i64) as usize
+            }
+            _ => return,
+        };
+        self.focus.set_focused(Some(focused));
+        self.interact = Interaction::None;
     }
 
     /// Adds a widget to the [Ui] and, if requested, clears the remaining horizontal space in the current row.
@@ -786,6 +2024,7 @@ where
     /// Adds a widget to the [Ui] and then starts a new row.
     ///
     /// The widget is drawn and its response is returned.
+    /// In a [Ui::vertical] column, only the item spacing is added after the widget.
     ///
     /// ## Returns
     ///
@@ -812,7 +2051,7 @@ where
     /// ```
     pub fn add(&mut self, widget: impl Widget) -> Response {
         let resp = self.add_raw(widget).unwrap_or_else(Response::from_error);
-        self.new_row();
+        self.end_widget();
         resp
     }
 
@@ -881,19 +2120,20 @@ where
     /// ```
     pub fn add_horizontal(&mut self, widget: impl Widget) -> Response {
         let resp = self.add_raw(widget).unwrap_or_else(Response::from_error);
-        // Allocate space between widgets; ignore space errors.
-        self.allocate_space_no_wrap(self.style().spacing.item_spacing)
-            .ok();
+        // Add space between widgets; spacing that doesn't fit is dropped.
+        self.placer.space(self.style().spacing.item_spacing);
         resp
     }
 
-    /// Draws a widget directly to the [Ui] without changing the layout.
+    /// Adds a widget to the [Ui] like [Ui::add], with extra margins and / or a different inner padding.
     ///
-    /// If a debug color is set, the widget's bounding area is drawn with that color.
+    /// The margins are added around the space the widget allocates (with [Ui::allocate_space] or the like),
+    /// and the widget is drawn inside of them. The padding replaces the style's button and default padding
+    /// while the widget is drawn. Pass [Margins] to only change the margins, or a [WidgetSpacing] to change both.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] wrapping a [Response] for the drawn widget.
+    /// A [Response] indicating the result of adding the widget. Its area doesn't include the margins.
     ///
     /// # Example
     ///
@@ -912,33 +2152,41 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// match ui.add_raw(widget) {
-    ///     Ok(response) => { /* widget drawn successfully */ },
-    ///     Err(e) => { /* handle error */ },
-    /// }
+    /// # use kolibri_embedded_gui::button::Button;
+    /// // 10px of extra space above and below the label
+    /// ui.add_with(widget, Margins::symmetric(0, 10));
+    ///
+    /// // a button with more padding and a margin on the left
+    /// ui.add_with(
+    ///     Button::new("Start"),
+    ///     WidgetSpacing::new()
+    ///         .margins(Margins { left: 20, ..Margins::default() })
+    ///         .padding(Size::new(12, 10)),
+    /// );
     /// ```
-    pub fn add_raw(&mut self, mut widget: impl Widget) -> GuiResult<Response> {
-        let res = widget.draw(self);
-        if let (Ok(res), Some(debug_color)) = (&res, self.debug_color) {
-            res.internal
-                .area
-                .draw_styled(
-                    &PrimitiveStyleBuilder::new()
-                        .stroke_color(debug_color)
-                        .stroke_width(1)
-                        .build(),
-                    &mut self.painter,
-                )
-                .ok();
+    pub fn add_with(&mut self, widget: impl Widget, spacing: impl Into<WidgetSpacing>) -> Response {
+        let resp = self
+            .add_raw_with(widget, spacing.into())
+            .unwrap_or_else(Response::from_error);
+        self.end_widget();
+        resp
+    }
+
+    /// Moves on after a widget added with [Ui::add] or the like: starts a new row (or adds spacing in columns,
+    /// and in [Ui::horizontal])
+    fn end_widget(&mut self) {
+        match self.placer.flow {
+            FlowDirection::Horizontal if self.combined.is_none() => self.new_row(),
+            _ => self.placer.space(self.style().spacing.item_spacing),
         }
-        res
     }
 
-    /// Returns an immutable reference to the current style of the [Ui].
+    /// Adds a widget to the current row of the [Ui] like [Ui::add_horizontal], with extra margins and / or
+    /// a different inner padding. See [Ui::add_with] for details.
     ///
     /// ## Returns
     ///
-    /// A reference to the [Style].
+    /// A [Response] indicating the result of adding the widget. Its area doesn't include the margins.
     ///
     /// # Example
     ///
@@ -957,17 +2205,44 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let current_style = ui.style();
+    /// ui.add_horizontal_with(widget, Margins::all(4));
     /// ```
-    pub fn style(&self) -> &Style<COL> {
-        &self.style
+    pub fn add_horizontal_with(
+        &mut self,
+        widget: impl Widget,
+        spacing: impl Into<WidgetSpacing>,
+    ) -> Response {
+        let resp = self
+            .add_raw_with(widget, spacing.into())
+            .unwrap_or_else(Response::from_error);
+        self.placer.space(self.style().spacing.item_spacing);
+        resp
     }
 
-    /// Returns a mutable reference to the current style of the [Ui].
+    /// Draws a widget with the given spacing overrides, without changing the layout afterwards
+    fn add_raw_with(&mut self, widget: impl Widget, spacing: WidgetSpacing) -> GuiResult<Response> {
+        let style_spacing = self.style.spacing;
+        if let Some(padding) = spacing.padding {
+            self.style.spacing.button_padding = padding;
+            self.style.spacing.default_padding = padding;
+        }
+        self.margins = Some(spacing.margins);
+
+        let res = self.add_raw(widget);
+
+        // the widget might not have allocated any space
+        self.margins = None;
+        self.style.spacing = style_spacing;
+        res
+    }
+
+    /// Draws a widget directly to the [Ui] without changing the layout.
+    ///
+    /// If a debug color is set, the widget's bounding area is drawn with that color.
     ///
     /// ## Returns
     ///
-    /// A mutable reference to the [Style].
+    /// A [GuiResult] wrapping a [Response] for the drawn widget.
     ///
     /// # Example
     ///
@@ -986,16 +2261,56 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let style = ui.style_mut();
-    /// style.background_color = Rgb565::BLACK;
+    /// match ui.add_raw(widget) {
+    ///     Ok(response) => { /* widget drawn successfully */ },
+    ///     Err(e) => { /* handle error */ },
+    /// }
     /// ```
-    pub fn style_mut(&mut self) -> &mut Style<COL> {
-        &mut self.style
+    pub fn add_raw(&mut self, mut widget: impl Widget) -> GuiResult<Response> {
+        if self.measuring.is_some() {
+            return self.allocate_measured(&widget);
+        }
+        let res = widget.draw(self);
+        match &res {
+            Ok(res) => self.combine(res),
+            Err(e) => self.combine(&Response::from_error(*e)),
+        }
+        if let (Ok(res), Some(debug_color)) = (&res, self.debug_color) {
+            res.internal
+                .area
+                .draw_styled(
+                    &PrimitiveStyleBuilder::new()
+                        .stroke_color(debug_color)
+                        .stroke_width(1)
+                        .build(),
+                    &mut self.painter,
+                )
+                .ok();
+        }
+        res
     }
 
-    /// Advances the layout to a new row in the [Ui].
+    /// Allocates the measured size of a widget instead of drawing it, in the measure pass of [Ui::horizontal].
     ///
-    /// This method uses the default spacing and widget height from the current style.
+    /// Widgets that can't be measured don't allocate anything, but mark the measurement as incomplete.
+    fn allocate_measured(&mut self, widget: &impl Widget) -> GuiResult<Response> {
+        let Some(size) = self.measure(widget) else {
+            self.measuring = Some(false);
+            return Ok(Response::new(InternalResponse::empty()).set_redraw(false));
+        };
+        self.allocate_space(size)?;
+        Ok(Response::new(InternalResponse::empty()).set_redraw(false))
+    }
+
+    /// Draws a widget inside the given (absolute) rectangle, without changing the layout.
+    ///
+    /// The widget keeps its own size and is placed in the rectangle according to the alignment of the [Ui]
+    /// (so by default, in its top left corner). Interactions are checked as usual. The position of the
+    /// following widgets isn't affected, so the widget may overlap them.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] indicating the result of adding the widget, with an error if it doesn't fit into the rectangle.
     ///
     /// # Example
     ///
@@ -1014,18 +2329,25 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.new_row();
+    /// # use kolibri_embedded_gui::button::Button;
+    /// // button in the bottom right corner of the screen
+    /// let corner = Rectangle::new(Point::new(260, 200), Size::new(60, 40));
+    /// if ui.put(corner, Button::new("Snap")).clicked() {
+    ///     // ...
+    /// }
     /// ```
-    pub fn new_row(&mut self) {
-        self.new_row_raw(self.style().spacing.item_spacing.height);
-        self.new_row_raw(self.style().default_widget_height);
+    pub fn put(&mut self, area: Rectangle, widget: impl Widget) -> Response {
+        self.area_ui(area, |ui| ui.add_raw(widget))
+            .0
+            .unwrap_or_else(Response::from_error)
     }
 
-    /// Advances the layout to a new row in the [Ui] with the specified height.
+    /// Measures a widget without drawing it, using the style of the [Ui] and the space still available
+    /// in it (see [Widget::measure]).
     ///
-    /// ## Parameters
+    /// ## Returns
     ///
-    /// - `height`: The height for the new row.
+    /// The size the widget will allocate, or `None` if the widget can't tell its size before drawing.
     ///
     /// # Example
     ///
@@ -1044,15 +2366,29 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.new_row_raw(20);
+    /// if let Some(size) = ui.measure(&widget) {
+    ///     // e.g. add a spacer to center the widget
+    /// }
     /// ```
-    pub fn new_row_raw(&mut self, height: u32) {
-        self.placer.new_row(height);
+    pub fn measure(&self, widget: &impl Widget) -> Option<Size> {
+        widget.measure(&self.style, self.space_available())
     }
 
-    /// Expands the current row height of the [Ui] to at least the given height.
+    /// Sets the alignment of the following widgets in their rows.
     ///
-    /// If the current row height is less than `height`, it is increased.
+    /// The horizontal alignment decides where in the row widgets are placed:
+    /// - [HorizontalAlign::Left]: widgets are placed from left to right.
+    /// - [HorizontalAlign::Center]: each widget is centered in the space that's still free in the row.
+    /// - [HorizontalAlign::Right]: widgets are placed from right to left, so the first widget
+    ///   added to a row ends up as its right-most one.
+    ///
+    /// To center or right-align a row of several widgets as a whole (keeping their order), add them
+    /// in [Ui::horizontal].
+    ///
+    /// The vertical alignment decides where widgets that are smaller than the row height are
+    /// placed within the row. With [VerticalAlign::Top] (the default), widgets get the full row height.
+    ///
+    /// The alignment stays in effect until it's changed again.
     ///
     /// # Example
     ///
@@ -1071,17 +2407,27 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.expand_row_height(30);
+    /// ui.set_align(Align(HorizontalAlign::Right, VerticalAlign::Center));
+    /// ui.add_horizontal(Label::new("right-most"));
+    /// ui.add_horizontal(Label::new("left of it"));
+    /// ui.new_row();
+    ///
+    /// // a right-aligned row, keeping the order of its widgets
+    /// ui.horizontal(|ui| {
+    ///     ui.add(Label::new("left"));
+    ///     ui.add(Label::new("right"));
+    ///     Ok(())
+    /// });
     /// ```
-    pub fn expand_row_height(&mut self, height: u32) {
-        self.placer.expand_row_height(height);
+    pub fn set_align(&mut self, align: Align) {
+        self.placer.set_align(align);
     }
 
-    /// Draws a [Drawable] item directly using the [Ui]'s underlying draw target.
+    /// Returns the current alignment of widgets in the [Ui].
     ///
     /// ## Returns
     ///
-    /// The output produced by drawing the item, or an error if drawing fails.
+    /// The current [Align].
     ///
     /// # Example
     ///
@@ -1100,24 +2446,19 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// # use embedded_graphics::primitives::PrimitiveStyle;
-    /// # let rectangle_shape = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
-    /// # // Make it drawable with a filled red style
-    /// # let rectangle = rectangle_shape.into_styled(PrimitiveStyle::with_fill(Rgb565::RED));
-    /// let result = ui.draw_raw(&rectangle);
+    /// let align = ui.align();
     /// ```
-    pub fn draw_raw<OUT>(
-        &mut self,
-        to_draw: &impl Drawable<Color = COL, Output = OUT>,
-    ) -> Result<OUT, DRAW::Error> {
-        to_draw.draw(self.painter.target)
+    pub fn align(&self) -> Align {
+        self.placer.align
     }
 
-    /// Returns the remaining available space for widget placement in the [Ui].
+    /// Returns the direction in which widgets are placed in the [Ui].
+    ///
+    /// This is [FlowDirection::Vertical] inside of [Ui::vertical], and [FlowDirection::Horizontal] otherwise.
     ///
     /// ## Returns
     ///
-    /// A [Size] representing the available width and height.
+    /// The current [FlowDirection].
     ///
     /// # Example
     ///
@@ -1136,18 +2477,21 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let space = ui.space_available();
-    /// println!("Available space: {:?}", space);
+    /// let flow = ui.flow_direction();
     /// ```
-    pub fn space_available(&self) -> Size {
-        self.placer.space_available()
+    pub fn flow_direction(&self) -> FlowDirection {
+        self.placer.flow
     }
 
-    /// Checks if the current interaction occurs within the specified area.
+    /// Sets the horizontal direction of the layout, e.g. [LayoutDirection::RightToLeft] for right-to-left languages.
     ///
-    /// ## Returns
+    /// With [LayoutDirection::RightToLeft], rows are filled from the right edge to the left, and widgets
+    /// with an internal order (e.g. the minimum of a [Slider](crate::slider::Slider), or the knob of a
+    /// [ToggleSwitch](crate::toggle_switch::ToggleSwitch)) are mirrored as well. The horizontal alignment
+    /// follows the layout direction, so [HorizontalAlign::Left] places widgets at the right edge.
     ///
-    /// The [Interaction] if the interaction's point is within the area, otherwise [Interaction::None].
+    /// As this is a runtime setting, it can be changed between frames, e.g. when switching languages.
+    /// Sub-[Ui]s (like [Ui::sub_ui] or [Ui::vertical]) inherit the direction of their parent.
     ///
     /// # Example
     ///
@@ -1166,29 +2510,19 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// # let some_rectangle = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
-    /// let interaction = ui.check_interact(some_rectangle);
+    /// ui.set_layout_direction(LayoutDirection::RightToLeft);
+    /// ui.add_horizontal(Label::new("right-most"));
+    /// ui.add_horizontal(Label::new("left of it"));
     /// ```
-    pub fn check_interact(&self, area: Rectangle) -> Interaction {
-        if self
-            .interact
-            .get_point()
-            .map(|pt| area.contains(pt))
-            .unwrap_or(false)
-        {
-            self.interact
-        } else {
-            Interaction::None
-        }
+    pub fn set_layout_direction(&mut self, direction: LayoutDirection) {
+        self.placer.set_direction(direction);
     }
 
-    /// Allocates an exact space in the [Ui] for a widget of the desired size.
-    ///
-    /// This method currently wraps [Ui::allocate_space] without extra logic.
+    /// Returns the horizontal direction of the layout.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] containing an [InternalResponse] with the allocated area and interaction.
+    /// The current [LayoutDirection].
     ///
     /// # Example
     ///
@@ -1207,19 +2541,18 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let allocation = ui.allocate_exact_size(Size::new(50, 30));
+    /// let rtl = ui.layout_direction() == LayoutDirection::RightToLeft;
     /// ```
-    pub fn allocate_exact_size(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
-        self.allocate_space(desired_size)
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.placer.direction
     }
 
-    /// Allocates space in the [Ui] for a widget of the desired size, with wrapping if needed.
-    ///
-    /// The allocated area is adjusted by the [Ui]'s bounds.
-    ///
-    /// ## Returns
+    /// Sets whether widgets that don't fit into the current row wrap to the next row.
     ///
-    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
+    /// Wrapped widgets are placed at the start of the next row, which begins
+    /// the vertical `item_spacing` below the current row.
+    /// With wrapping disabled, adding a widget that doesn't fit results in [GuiError::NoSpaceLeft].
+    /// Widgets that don't even fit into an empty row result in [GuiError::WidgetTooLarge].
     ///
     /// # Example
     ///
@@ -1238,28 +2571,20 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let allocation = ui.allocate_space(Size::new(100, 40));
+    /// ui.set_wrap(false);
     /// ```
-    pub fn allocate_space(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
-        let rect = self.placer.next(desired_size).map(|mut rect| {
-            rect.top_left.add_assign(self.bounds.top_left);
-            rect
-        })?;
-        let inter = self.check_interact(rect);
-
-        Ok(InternalResponse {
-            area: rect,
-            interaction: inter,
-        })
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.placer.set_wrap(wrap);
     }
 
-    /// Allocates space in the [Ui] for a widget of the desired size without wrapping.
-    ///
-    /// The allocated area is adjusted by the [Ui]'s bounds.
+    /// Sets whether widgets that can shrink (e.g. a [Label](crate::label::Label), which truncates its text)
+    /// are shrunk to the width that's left, instead of failing with [GuiError::NoSpaceLeft]
+    /// or [GuiError::WidgetTooLarge].
     ///
-    /// ## Returns
+    /// If wrapping is enabled, widgets still wrap to the next row first, and are only shrunk
+    /// if they don't fit into the next row either. Disabled by default.
     ///
-    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
+    /// Widgets opt into shrinking by allocating their space with [Ui::allocate_space_shrinkable].
     ///
     /// # Example
     ///
@@ -1278,27 +2603,18 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let allocation = ui.allocate_space_no_wrap(Size::new(80, 25));
+    /// ui.set_shrink_to_fit(true);
+    /// ui.add(Label::new("A very long text that's truncated if it doesn't fit"));
     /// ```
-    pub fn allocate_space_no_wrap(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
-        let area = self.placer.next_no_wrap(desired_size).map(|mut rect| {
-            rect.top_left.add_assign(self.bounds.top_left);
-            rect
-        })?;
-
-        let inter = self.check_interact(area);
-
-        Ok(InternalResponse {
-            area,
-            interaction: inter,
-        })
+    pub fn set_shrink_to_fit(&mut self, shrink_to_fit: bool) {
+        self.placer.set_shrink_to_fit(shrink_to_fit);
     }
 
-    /// Returns the current row height used in the [Ui]'s layout.
+    /// Returns an immutable reference to the current style of the [Ui].
     ///
     /// ## Returns
     ///
-    /// The current row height as a `u32`.
+    /// A reference to the [Style].
     ///
     /// # Example
     ///
@@ -1317,25 +2633,17 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let row_height = ui.get_row_height();
-    /// println!("Row height: {}", row_height);
+    /// let current_style = ui.style();
     /// ```
-    pub fn get_row_height(&self) -> u32 {
-        self.placer.row_height()
+    pub fn style(&self) -> &Style<COL> {
+        &self.style
     }
-}
 
-// -- Clearing methods --
-impl<COL, DRAW> Ui<'_, DRAW, COL>
-where
-    DRAW: DrawTarget<Color = COL>,
-    COL: PixelColor,
-{
-    /// Returns whether the [Ui]'s background was cleared this frame.
+    /// Returns a mutable reference to the current style of the [Ui].
     ///
     /// ## Returns
     ///
-    /// `true` if the background was cleared, `false` otherwise.
+    /// A mutable reference to the [Style].
     ///
     /// # Example
     ///
@@ -1354,19 +2662,17 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// if ui.cleared() {
-    ///     println!("Background cleared");
-    /// }
+    /// let style = ui.style_mut();
+    /// style.background_color = Rgb565::BLACK;
     /// ```
-    pub fn cleared(&self) -> bool {
-        self.cleared
+    pub fn style_mut(&mut self) -> &mut Style<COL> {
+        &mut self.style
     }
 
-    /// Clears the specified area in the [Ui] using the background color.
-    ///
-    /// ## Returns
+    /// Advances the layout to a new row in the [Ui].
     ///
-    /// A [GuiResult] indicating success or error.
+    /// This method uses the default spacing and widget height from the current style.
+    /// In a [Ui::vertical] column, this advances to a new column instead.
     ///
     /// # Example
     ///
@@ -1385,18 +2691,26 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.clear_area(Rectangle::new(Point::new(0,0), Size::new(100, 50))).unwrap();
+    /// ui.new_row();
     /// ```
-    pub fn clear_area(&mut self, area: Rectangle) -> GuiResult<()> {
-        self.draw(&area.into_styled(PrimitiveStyle::with_fill(self.style.background_color)))
-            .map_err(|_| GuiError::DrawError(Some("Couldn't clear area")))
+    pub fn new_row(&mut self) {
+        match self.placer.flow {
+            FlowDirection::Horizontal => {
+                self.new_row_raw(self.style().spacing.item_spacing.height);
+                self.new_row_raw(self.style().default_widget_height);
+            }
+            FlowDirection::Vertical => {
+                self.new_row_raw(self.style().spacing.item_spacing.width);
+                self.new_row_raw(0);
+            }
+        }
     }
 
-    /// Clears the current row in the [Ui] with the background color.
+    /// Advances the layout to a new row in the [Ui] with the specified height.
     ///
-    /// ## Returns
+    /// ## Parameters
     ///
-    /// A [GuiResult] indicating whether the row was successfully cleared.
+    /// - `height`: The height for the new row.
     ///
     /// # Example
     ///
@@ -1415,24 +2729,15 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.clear_row().unwrap();
+    /// ui.new_row_raw(20);
     /// ```
-    pub fn clear_row(&mut self) -> GuiResult<()> {
-        let row_height = self.placer.row_height();
-        let row_rect = Rectangle::new(
-            Point::new(0, self.placer.pos.y),
-            Size::new(self.placer.bounds.width, row_height),
-        );
-        self.clear_area(row_rect)
+    pub fn new_row_raw(&mut self, height: u32) {
+        self.placer.new_row(height);
     }
 
-    /// Clears the current row from the current widget position to the end of the row.
-    ///
-    /// This is useful for removing any rendering remains of partially drawn widgets.
-    ///
-    /// ## Returns
+    /// Expands the current row height of the [Ui] to at least the given height.
     ///
-    /// A [GuiResult] indicating success or error.
+    /// If the current row height is less than `height`, it is increased.
     ///
     /// # Example
     ///
@@ -1451,31 +2756,17 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.clear_row_to_end().unwrap();
+    /// ui.expand_row_height(30);
     /// ```
-    pub fn clear_row_to_end(&mut self) -> GuiResult<()> {
-        let col_height = self.placer.row_height;
-        let col_rect = Rectangle::new(
-            // clear right to widget bounds
-            Point::new(
-                self.placer.pos.x + self.style.spacing.window_border_padding.width as i32,
-                self.placer.pos.y,
-            ),
-            Size::new(
-                (self.placer.bounds.width as i32 - self.placer.pos.x).max(0) as u32,
-                col_height,
-            ),
-        );
-        self.clear_area(col_rect)
+    pub fn expand_row_height(&mut self, height: u32) {
+        self.placer.expand_row_height(height);
     }
 
-    /// Clears the [Ui] from the current placement position down to the bottom of the screen.
-    ///
-    /// **Warning:** This clears the entire screen area from the current row downwards.
+    /// Draws a [Drawable] item directly using the [Ui]'s underlying draw target.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// The output produced by drawing the item, or an error if drawing fails.
     ///
     /// # Example
     ///
@@ -1494,25 +2785,24 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.clear_to_bottom().unwrap();
+    /// # use embedded_graphics::primitives::PrimitiveStyle;
+    /// # let rectangle_shape = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
+    /// # // Make it drawable with a filled red style
+    /// # let rectangle = rectangle_shape.into_styled(PrimitiveStyle::with_fill(Rgb565::RED));
+    /// let result = ui.draw_raw(&rectangle);
     /// ```
-    pub fn clear_to_bottom(&mut self) -> GuiResult<()> {
-        self.clear_area(Rectangle::new(
-            Point::new(0, self.placer.pos.y),
-            Size::new(
-                self.placer.bounds.width,
-                self.placer.bounds.height - self.placer.pos.y as u32,
-            ),
-        ))
+    pub fn draw_raw<OUT>(
+        &mut self,
+        to_draw: &impl Drawable<Color = COL, Output = OUT>,
+    ) -> Result<OUT, DRAW::Error> {
+        to_draw.draw(&mut self.painter.output())
     }
 
-    /// Clears the entire background of the [Ui] with the background color defined in the style.
-    ///
-    /// This method updates the [Ui]'s cleared flag.
+    /// Returns the remaining available space for widget placement in the [Ui].
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// A [Size] representing the available width and height.
     ///
     /// # Example
     ///
@@ -1531,42 +2821,20 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.clear_background().unwrap();
+    /// let space = ui.space_available();
+    /// println!("Available space: {:?}", space);
     /// ```
-    pub fn clear_background(&mut self) -> GuiResult<()> {
-        self.cleared = true;
-
-        // clear background
-        let real_bg = Rectangle::new(
-            self.bounds.top_left.sub(Point::new(
-                self.style.spacing.window_border_padding.width as i32,
-                self.style.spacing.window_border_padding.height as i32,
-            )),
-            self.bounds
-                .size
-                .saturating_add(self.style.spacing.window_border_padding * 2),
-        );
-
-        real_bg
-            .draw_styled(
-                &PrimitiveStyleBuilder::new()
-                    .fill_color(self.style.background_color)
-                    .build(),
-                self.painter.target,
-            )
-            .map_err(|_| GuiError::DrawError(Some("Couldn't clear GUI Background")))
+    pub fn space_available(&self) -> Size {
+        self.placer.space_available()
     }
-}
 
-// -- Drawing methods --
-impl<'a, COL, DRAW> Ui<'a, DRAW, COL>
-where
-    DRAW: DrawTarget<Color = COL>,
-    COL: PixelColor,
-{
-    /// Sets the internal drawing buffer for the [Ui].
+    /// Checks if the current interaction occurs within the specified area.
     ///
-    /// This buffer is used for optimized drawing and is optional.
+    /// ## Returns
+    ///
+    /// The [Interaction] if the interaction's point is within the area (and the visible part of the [Ui],
+    /// e.g. not covered by a [window](crate::window)),
+    /// otherwise [Interaction::None].
     ///
     /// # Example
     ///
@@ -1585,20 +2853,29 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let mut buffer = [Rgb565::BLACK; 10000];
-    /// ui.set_buffer(&mut buffer);
+    /// # let some_rectangle = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
+    /// let interaction = ui.check_interact(some_rectangle);
     /// ```
-    pub fn set_buffer(&mut self, buffer: &'a mut [COL]) {
-        self.painter.set_buffer(buffer);
+    pub fn check_interact(&self, area: Rectangle) -> Interaction {
+        if self
+            .interact
+            .get_point()
+            .map(|pt| area.contains(pt) && self.painter.is_visible(pt))
+            .unwrap_or(false)
+        {
+            self.interact
+        } else {
+            Interaction::None
+        }
     }
 
-    /// Begins the drawing process for a specified area in the [Ui].
+    /// Allocates an exact space in the [Ui] for a widget of the desired size.
     ///
-    /// This initializes the drawing buffer (if set) and clears it with the background color.
+    /// This method currently wraps [Ui::allocate_space] without extra logic.
     ///
-    /// ## Panics
+    /// ## Returns
     ///
-    /// Panics if the underlying [Painter] is already using its framebuffer.
+    /// A [GuiResult] containing an [InternalResponse] with the allocated area and interaction.
     ///
     /// # Example
     ///
@@ -1617,19 +2894,19 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let draw_area = Rectangle::new(Point::new(30, 20), Size::new(100, 150));
-    /// ui.start_drawing(&draw_area);
+    /// let allocation = ui.allocate_exact_size(Size::new(50, 30));
     /// ```
-    pub fn start_drawing(&mut self, area: &Rectangle) {
-        self.painter.start_drawing(area);
-        self.painter.clear_buffer(self.style.background_color);
+    pub fn allocate_exact_size(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        self.allocate_space(desired_size)
     }
 
-    /// Clears the current drawing buffer with the specified color.
+    /// Allocates space in the [Ui] for a widget of the desired size, with wrapping if needed.
+    ///
+    /// The allocated area is adjusted by the [Ui]'s bounds.
     ///
     /// ## Returns
     ///
-    /// `true` if the buffer was successfully cleared, or `false` if no buffer is present.
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
     ///
     /// # Example
     ///
@@ -1648,20 +2925,22 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// let cleared = ui.clear_buffer_raw(Rgb565::BLACK);
-    /// println!("Buffer cleared: {}", cleared);
+    /// let allocation = ui.allocate_space(Size::new(100, 40));
     /// ```
-    pub fn clear_buffer_raw(&mut self, color: COL) -> bool {
-        self.painter.clear_buffer(color)
+    pub fn allocate_space(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        self.placer.set_spacing(self.style.spacing.item_spacing);
+        self.allocate_with_margins(desired_size, Placer::next)
     }
 
-    /// Finalizes the drawing process for the [Ui].
+    /// Allocates space in the [Ui] for a widget that can shrink, with wrapping if needed.
     ///
-    /// This flushes any buffered drawing to the underlying draw target.
+    /// Like [Ui::allocate_space], but if shrink to fit is enabled (see [Ui::set_shrink_to_fit]), a widget that
+    /// doesn't fit is given the width that's left instead. The widget has to cope with the allocated area
+    /// being narrower than `desired_size`.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
     ///
     /// # Example
     ///
@@ -1680,19 +2959,21 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.finalize().unwrap();
+    /// let allocation = ui.allocate_space_shrinkable(Size::new(500, 20)).unwrap();
+    /// // truncate the content to allocation.area.size.width
     /// ```
-    pub fn finalize(&mut self) -> GuiResult<()> {
-        self.painter.finalize()
+    pub fn allocate_space_shrinkable(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        self.placer.set_spacing(self.style.spacing.item_spacing);
+        self.allocate_with_margins(desired_size, Placer::next_shrinkable)
     }
 
-    /// Draws a [Drawable] item onto the [Ui].
+    /// Allocates space in the [Ui] for a widget of the desired size without wrapping.
     ///
-    /// If a buffer is active, the item is drawn to the buffer; otherwise, it is drawn directly.
+    /// The allocated area is adjusted by the [Ui]'s bounds.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating whether the drawing operation was successful.
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
     ///
     /// # Example
     ///
@@ -1711,30 +2992,21 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// # use embedded_graphics::primitives::PrimitiveStyle;
-    /// # let rectangle_shape = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
-    /// # // Make it drawable with a filled red style
-    /// # let rectangle = rectangle_shape.into_styled(PrimitiveStyle::with_fill(Rgb565::RED));
-    /// ui.draw(&rectangle).unwrap();
+    /// let allocation = ui.allocate_space_no_wrap(Size::new(80, 25));
     /// ```
-    pub fn draw(&mut self, item: &impl Drawable<Color = COL>) -> GuiResult<()> {
-        self.painter.draw(item)
+    pub fn allocate_space_no_wrap(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        self.allocate_with_margins(desired_size, Placer::next_no_wrap)
     }
-}
 
-// -- Sub-[Ui] methods --
-impl<COL, DRAW> Ui<'_, DRAW, COL>
-where
-    DRAW: DrawTarget<Color = COL>,
-    COL: PixelColor,
-{
-    /// Creates a sub-[Ui] with the given bounds without performing extra bounds checks.
+    /// Allocates space in the [Ui] for a widget that can be focused (see [Ui::set_focus_state]), with wrapping if needed.
     ///
-    /// This sub-[Ui] is useful for drawing to a specific area with its own layout and style.
+    /// Like [Ui::allocate_space], but the widget is numbered for the focus navigation, and gets a focus ring
+    /// while it's focused. If it's focused and the interaction is [Interaction::Activate], the returned interaction
+    /// is a [Interaction::Release] in the center of the area, so widgets react to it like to a click.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction.
     ///
     /// # Example
     ///
@@ -1752,56 +3024,27 @@ where
     /// # let output_settings = OutputSettingsBuilder::new().build();
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
-    /// # let mut widget = Label::new("Hi");
-    /// let sub_bounds = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
-    /// ui.unchecked_sub_ui(sub_bounds, |sub_ui| {
-    ///     sub_ui.add(widget);
-    ///     Ok(())
-    /// }).unwrap();
+    /// let allocation = ui.allocate_focusable_space(Size::new(60, 20)).unwrap();
+    /// let clicked = matches!(allocation.interaction, Interaction::Release(_));
     /// ```
-    pub fn unchecked_sub_ui<F>(&mut self, bounds: Rectangle, f: F) -> GuiResult<()>
-    where
-        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
-    {
-        let bounds = Rectangle::new(
-            bounds.top_left.add(Point::new(
-                self.style.spacing.window_border_padding.height as i32,
-                self.style.spacing.window_border_padding.width as i32,
-            )),
-            bounds
-                .size
-                .saturating_sub(self.style.spacing.window_border_padding * 2),
-        );
-
-        let placer = Placer::new(
-            bounds.size,
-            true,
-            Align(HorizontalAlign::Left, VerticalAlign::Top),
-        );
-
-        self.painter.with_subpainter(|painter| {
-            let mut sub_ui = Ui {
-                painter,
-                bounds,
-                style: self.style,
-                interact: self.interact,
-                placer,
-                cleared: false,
-                debug_color: self.debug_color,
-            };
-            (f)(&mut sub_ui)
-        })?;
-
-        Ok(())
+    pub fn allocate_focusable_space(&mut self, desired_size: Size) -> GuiResult<InternalResponse> {
+        self.allocate_focusable(desired_size, false)
+            .map(|(iresponse, _)| iresponse)
     }
 
-    /// Creates a sub-[Ui] that shares the same bounds as the parent [Ui].
+    /// Allocates space in the [Ui] for a widget that can be focused and edited with a rotary encoder
+    /// (see [Interaction::Scroll]), with wrapping if needed.
     ///
-    /// Changes to the sub-[Ui]'s layout are reflected in the parent.
+    /// Like [Ui::allocate_focusable_space], but if the widget is focused, [Interaction::Activate] enters
+    /// or leaves the edit mode (instead of clicking it). In edit mode, the returned interaction is the
+    /// [Interaction::Scroll] (if any), and the focus ring is drawn in the style's secondary color.
+    /// [Interaction::FocusNext] and [Interaction::FocusPrev] (or [Interaction::Navigate] to the right and left)
+    /// are returned as a scroll by one step in edit mode, for devices with buttons only.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// A [GuiResult] containing an [InternalResponse] with the allocated rectangle and interaction,
+    /// and whether the widget is in edit mode.
     ///
     /// # Example
     ///
@@ -1819,97 +3062,154 @@ where
     /// # let output_settings = OutputSettingsBuilder::new().build();
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
-    /// # let mut widget = Label::new("Hi");
-    /// ui.sub_ui(|sub_ui| {
-    ///     sub_ui.add(widget);
-    ///     Ok(())
-    /// }).unwrap();
+    /// # let mut value = 0;
+    /// let (allocation, editing) = ui.allocate_editable_space(Size::new(60, 20)).unwrap();
+    /// if let Interaction::Scroll(delta) = allocation.interaction {
+    ///     value += delta;
+    /// }
     /// ```
-    pub fn sub_ui<F>(&mut self, f: F) -> GuiResult<()>
-    where
-        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
-    {
-        self.painter.with_subpainter(|painter| {
-            let mut sub_ui = Ui {
-                painter,
-                bounds: self.bounds,
-                style: self.style,
-                interact: self.interact,
-                placer: self.placer.clone(),
-                cleared: false,
-                debug_color: self.debug_color,
-            };
-            let res = (f)(&mut sub_ui);
-            self.placer = sub_ui.placer;
-            res
-        })?;
+    pub fn allocate_editable_space(
+        &mut self,
+        desired_size: Size,
+    ) -> GuiResult<(InternalResponse, bool)> {
+        self.allocate_focusable(desired_size, true)
+    }
 
-        Ok(())
+    /// Allocates space for a focusable widget, returning whether it's in edit mode (if it's `editable`)
+    fn allocate_focusable(
+        &mut self,
+        desired_size: Size,
+        editable: bool,
+    ) -> GuiResult<(InternalResponse, bool)> {
+        let mut iresponse = self.allocate_space(desired_size)?;
+
+        self.navigate_focus();
+        let index = self.focus_index;
+        self.focus_index += 1;
+        if self.focus.focused != Some(index) {
+            if let Interaction::Navigate(direction) = self.interact {
+                self.track_focus_target(index, iresponse.area, direction);
+            }
+            return Ok((iresponse, false));
+        }
+
+        match (self.interact, editable) {
+            (Interaction::Activate, true) => self.focus.editing = !self.focus.editing,
+            (Interaction::Activate, false) => {
+                iresponse.interaction = Interaction::Release(iresponse.area.center())
+            }
+            (Interaction::Scroll(_), true) if self.focus.editing => {
+                iresponse.interaction = self.interact
+            }
+            (Interaction::Back, _) => iresponse.interaction = Interaction::Back,
+            _ => {}
+        }
+        // e.g. if the layout changed, and another widget has the index now
+        self.focus.editing &= editable;
+
+        self.focus.area = Some(iresponse.area);
+        self.show_focus_ring(iresponse.area)?;
+        Ok((iresponse, self.focus.editing))
     }
 
-    /// Creates a right-side panel sub-[Ui] with the specified width.
-    ///
-    /// If `allow_smaller` is false, an error is returned if there is insufficient space.
-    ///
-    /// ## Returns
+    /// Keeps the widget at `area` as the target of [Interaction::Navigate], if it's the nearest one so far.
     ///
-    /// A [GuiResult] indicating success or error.
-    ///
-    /// # Example
-    ///
-    /// ```no_run
-    /// # use embedded_graphics::pixelcolor::Rgb565;
-    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
-    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
-    /// # use kolibri_embedded_gui::ui::Ui;
-    /// # use embedded_graphics::prelude::*;
-    /// # use embedded_graphics::primitives::Rectangle;
-    /// # use embedded_iconoir::prelude::*;
-    /// # use kolibri_embedded_gui::ui::*;
-    /// # use kolibri_embedded_gui::label::*;
-    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
-    /// # let output_settings = OutputSettingsBuilder::new().build();
-    /// # let mut window = Window::new("Kolibri Example", &output_settings);
-    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
-    /// # let mut widget = Label::new("Hi");
-    /// ui.right_panel_ui(100, false, |sub_ui| {
-    ///     sub_ui.add(widget);
-    ///     Ok(())
-    /// }).unwrap();
-    /// ```
-    pub fn right_panel_ui<F>(&mut self, width: u32, allow_smaller: bool, f: F) -> GuiResult<()>
-    where
-        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
-    {
-        let bounds = self.placer.bounds;
-        let y = self.placer.pos.y as u32;
-        let max_width = bounds.width - self.placer.pos.x as u32;
-        let max_height = bounds.height - y;
+    /// Widgets further in the direction, and further off to the side, are further away (the latter counting double).
+    /// Widgets behind the focused one are treated as if the screen wrapped around.
+    fn track_focus_target(&mut self, index: usize, area: Rectangle, direction: Direction) {
+        let Some(origin) = self.focus.area else {
+            return;
+        };
+        let screen = self
+            .painter
+            .rotation
+            .logical_bounds(self.painter.target.bounding_box())
+            .size;
+        let (from, to) = (origin.center(), area.center());
+        let (along, across, extent) = match direction {
+            Direction::Up => (from.y - to.y, to.x - from.x, screen.height),
+            Direction::Down => (to.y - from.y, to.x - from.x, screen.height),
+            Direction::Left => (from.x - to.x, to.y - from.y, screen.width),
+            Direction::Right => (to.x - from.x, to.y - from.y, screen.width),
+        };
+        let (wrapped, along) = if along > 0 {
+            (false, along)
+        } else {
+            (true, along + extent as i32)
+        };
 
-        if width > max_width && !allow_smaller {
-            return Err(GuiError::BoundsError);
+        let score = (wrapped, along.unsigned_abs() + 2 * across.unsigned_abs());
+        if self.focus_target.map_or(true, |(best, _, _)| score < best) {
+            self.focus_target = Some((score, index, area));
         }
+    }
 
-        self.placer.bounds.width -= min(width, max_width);
+    /// Draws the focus ring just outside of the (absolute) `area`, removing the previous one.
+    ///
+    /// In edit mode, the ring is drawn in the secondary color.
+    fn show_focus_ring(&mut self, area: Rectangle) -> GuiResult<()> {
+        let width = max(self.style.highlight_border_width, 1);
+        let ring = Rectangle::new(
+            area.top_left - Point::new(width as i32, width as i32),
+            area.size + Size::new(2 * width, 2 * width),
+        );
+        let editing = self.focus.editing;
+        if self.focus.ring != Some((ring, editing)) || self.cleared {
+            if let Some((old, _)) = self.focus.ring.filter(|(old, _)| *old != ring) {
+                self.draw_focus_ring(old, self.style.background_color)?;
+            }
+            let color = if editing {
+                self.style.secondary_color
+            } else {
+                self.style.highlight_border_color
+            };
+            self.draw_focus_ring(ring, color)?;
+            self.focus.ring = Some((ring, editing));
+        }
+        Ok(())
+    }
+
+    /// Draws (or, in the background color, removes) the focus ring along the inside of `ring`
+    fn draw_focus_ring(&mut self, ring: Rectangle, color: COL) -> GuiResult<()> {
+        let style = PrimitiveStyleBuilder::new()
+            .stroke_color(color)
+            .stroke_width(max(self.style.highlight_border_width, 1))
+            .stroke_alignment(StrokeAlignment::Inside)
+            .build();
+        ring.draw_styled(&style, &mut self.painter)
+            .map_err(|_| GuiError::DrawError(Some("Couldn't draw focus ring")))
+    }
 
+    /// Allocates `desired_size` using `next`, adding the pending margins (see [Ui::add_with]) around it.
+    ///
+    /// The returned area is the (absolute) area inside the margins.
+    fn allocate_with_margins(
+        &mut self,
+        desired_size: Size,
+        next: impl FnOnce(&mut Placer, Size) -> GuiResult<Rectangle>,
+    ) -> GuiResult<InternalResponse> {
+        let margins = self.margins.take().unwrap_or_default();
+        let outer = next(&mut self.placer, desired_size + margins.size())?;
         let area = Rectangle::new(
-            Point::new((bounds.width - min(width, max_width)) as i32, y as i32),
-            Size::new(
-                bounds.width - (bounds.width - min(width, max_width)),
-                max_height,
-            ),
+            self.bounds.top_left
+                + outer.top_left
+                + Point::new(margins.left as i32, margins.top as i32),
+            outer.size.saturating_sub(margins.size()),
         );
 
-        self.unchecked_sub_ui(area, f)
+        let inter = self.check_interact(area);
+
+        Ok(InternalResponse {
+            area,
+            interaction: inter,
+        })
     }
 
-    /// Creates a centered sub-[Ui] panel with the specified width and height.
-    ///
-    /// The panel is centered within the current bounds. An error is returned if the dimensions exceed the available space.
+    /// Returns the current row height used in the [Ui]'s layout.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// The current row height as a `u32`.
     ///
     /// # Example
     ///
@@ -1928,53 +3228,1870 @@ where
     /// # let mut window = Window::new("Kolibri Example", &output_settings);
     /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
     /// # let mut widget = Label::new("Hi");
-    /// ui.central_centered_panel_ui(200, 150, |sub_ui| {
-    ///     sub_ui.add(widget);
-    ///     Ok(())
-    /// }).unwrap();
+    /// let row_height = ui.get_row_height();
+    /// println!("Row height: {}", row_height);
     /// ```
-    pub fn central_centered_panel_ui<F>(&mut self, width: u32, height: u32, f: F) -> GuiResult<()>
-    where
-        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
-    {
-        let bounds = self.placer.bounds;
-        let max_width = bounds.width;
-        let max_height = bounds.height;
-
-        if width > max_width {
-            return Err(GuiError::BoundsError);
-        }
-
-        if height > max_height {
-            return Err(GuiError::BoundsError);
-        }
-
-        self.placer.bounds.width -= min(width, max_width);
-        self.placer.bounds.height -= min(height, max_height);
-
-        let area = Rectangle::new(
-            Point::new(
-                ((bounds.width - width) / 2) as i32,
-                ((bounds.height - height) / 2) as i32,
-            ),
-            Size::new(width, height),
-        );
-
-        self.unchecked_sub_ui(area, f)
+    pub fn get_row_height(&self) -> u32 {
+        self.placer.row_height()
     }
 }
 
-// -- Debug drawing methods --
+// -- Clearing methods --
 impl<COL, DRAW> Ui<'_, DRAW, COL>
 where
     DRAW: DrawTarget<Color = COL>,
     COL: PixelColor,
 {
-    /// Draws a debug outline around the [Ui]'s bounds using the specified color.
+    /// Returns whether the [Ui]'s background was cleared this frame.
     ///
     /// ## Returns
     ///
-    /// A [GuiResult] indicating success or error.
+    /// `true` if the background was cleared, `false` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// if ui.cleared() {
+    ///     println!("Background cleared");
+    /// }
+    /// ```
+    pub fn cleared(&self) -> bool {
+        self.cleared
+    }
+
+    /// Clears the specified area in the [Ui] using the background color.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.clear_area(Rectangle::new(Point::new(0,0), Size::new(100, 50))).unwrap();
+    /// ```
+    pub fn clear_area(&mut self, area: Rectangle) -> GuiResult<()> {
+        self.draw(&area.into_styled(PrimitiveStyle::with_fill(self.style.background_color)))
+            .map_err(|_| GuiError::DrawError(Some("Couldn't clear area")))
+    }
+
+    /// Clears the current row in the [Ui] with the background color.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating whether the row was successfully cleared.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.clear_row().unwrap();
+    /// ```
+    pub fn clear_row(&mut self) -> GuiResult<()> {
+        let row_height = self.placer.row_height();
+        let row_rect = Rectangle::new(
+            Point::new(0, self.placer.pos.y),
+            Size::new(self.placer.bounds.width, row_height),
+        );
+        self.clear_area(row_rect)
+    }
+
+    /// Clears the current row from the current widget position to the end of the row.
+    ///
+    /// This is useful for removing any rendering remains of partially drawn widgets.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.clear_row_to_end().unwrap();
+    /// ```
+    pub fn clear_row_to_end(&mut self) -> GuiResult<()> {
+        let col_height = self.placer.row_height;
+        let col_rect = Rectangle::new(
+            // clear right to widget bounds
+            Point::new(
+                self.placer.pos.x + self.style.spacing.window_border_padding.width as i32,
+                self.placer.pos.y,
+            ),
+            Size::new(
+                (self.placer.row_end as i32 - self.placer.pos.x).max(0) as u32,
+                col_height,
+            ),
+        );
+        self.clear_area(self.placer.mirror(col_rect))
+    }
+
+    /// Clears the [Ui] from the current placement position down to the bottom of the screen.
+    ///
+    /// **Warning:** This clears the entire screen area from the current row downwards.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.clear_to_bottom().unwrap();
+    /// ```
+    pub fn clear_to_bottom(&mut self) -> GuiResult<()> {
+        self.clear_area(Rectangle::new(
+            Point::new(0, self.placer.pos.y),
+            Size::new(
+                self.placer.bounds.width,
+                self.placer.bounds.height - self.placer.pos.y as u32,
+            ),
+        ))
+    }
+
+    /// Clears the entire background of the [Ui] with the background color defined in the style.
+    ///
+    /// This method updates the [Ui]'s cleared flag.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.clear_background().unwrap();
+    /// ```
+    pub fn clear_background(&mut self) -> GuiResult<()> {
+        self.cleared = true;
+
+        // clear background
+        let real_bg = Rectangle::new(
+            self.bounds.top_left.sub(Point::new(
+                self.style.spacing.window_border_padding.width as i32,
+                self.style.spacing.window_border_padding.height as i32,
+            )),
+            self.bounds
+                .size
+                .saturating_add(self.style.spacing.window_border_padding * 2),
+        );
+
+        real_bg
+            .draw_styled(
+                &PrimitiveStyleBuilder::new()
+                    .fill_color(self.style.background_color)
+                    .build(),
+                &mut self.painter.output(),
+            )
+            .map_err(|_| GuiError::DrawError(Some("Couldn't clear GUI Background")))
+    }
+}
+
+// -- Drawing methods --
+impl<'a, COL, DRAW> Ui<'a, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Sets the internal drawing buffer for the [Ui].
+    ///
+    /// This buffer is used for optimized drawing and is optional.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// let mut buffer = [Rgb565::BLACK; 10000];
+    /// ui.set_buffer(&mut buffer);
+    /// ```
+    pub fn set_buffer(&mut self, buffer: &'a mut [COL]) {
+        self.painter.set_buffer(buffer);
+    }
+
+    /// Begins the drawing process for a specified area in the [Ui].
+    ///
+    /// This initializes the drawing buffer (if set) and clears it with the background color.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the underlying [Painter] is already using its framebuffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// let draw_area = Rectangle::new(Point::new(30, 20), Size::new(100, 150));
+    /// ui.start_drawing(&draw_area);
+    /// ```
+    pub fn start_drawing(&mut self, area: &Rectangle) {
+        self.painter.start_drawing(area);
+        self.painter.clear_buffer(self.style.background_color);
+    }
+
+    /// Clears the current drawing buffer with the specified color.
+    ///
+    /// ## Returns
+    ///
+    /// `true` if the buffer was successfully cleared, or `false` if no buffer is present.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// let cleared = ui.clear_buffer_raw(Rgb565::BLACK);
+    /// println!("Buffer cleared: {}", cleared);
+    /// ```
+    pub fn clear_buffer_raw(&mut self, color: COL) -> bool {
+        self.painter.clear_buffer(color)
+    }
+
+    /// Finalizes the drawing process for the [Ui].
+    ///
+    /// This flushes any buffered drawing to the underlying draw target.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.finalize().unwrap();
+    /// ```
+    pub fn finalize(&mut self) -> GuiResult<()> {
+        self.painter.finalize()
+    }
+
+    /// Draws a [Drawable] item onto the [Ui].
+    ///
+    /// If a buffer is active, the item is drawn to the buffer; otherwise, it is drawn directly.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating whether the drawing operation was successful.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// # use embedded_graphics::primitives::PrimitiveStyle;
+    /// # let rectangle_shape = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
+    /// # // Make it drawable with a filled red style
+    /// # let rectangle = rectangle_shape.into_styled(PrimitiveStyle::with_fill(Rgb565::RED));
+    /// ui.draw(&rectangle).unwrap();
+    /// ```
+    pub fn draw(&mut self, item: &impl Drawable<Color = COL>) -> GuiResult<()> {
+        self.painter.draw(item)
+    }
+}
+
+// -- Sub-[Ui] methods --
+impl<COL, DRAW> Ui<'_, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Creates a sub-[Ui] with the given bounds without performing extra bounds checks.
+    ///
+    /// This sub-[Ui] is useful for drawing to a specific area with its own layout and style.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// let sub_bounds = Rectangle::new(Point::new(50, 0), Size::new(200, 150));
+    /// ui.unchecked_sub_ui(sub_bounds, |sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn unchecked_sub_ui<F>(&mut self, bounds: Rectangle, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let bounds = Rectangle::new(
+            bounds.top_left.add(Point::new(
+                self.style.spacing.window_border_padding.height as i32,
+                self.style.spacing.window_border_padding.width as i32,
+            )),
+            bounds
+                .size
+                .saturating_sub(self.style.spacing.window_border_padding * 2),
+        );
+
+        let placer = Placer::new(
+            bounds.size,
+            true,
+            Align(HorizontalAlign::Left, VerticalAlign::Top),
+        );
+
+        self.child_ui(bounds, placer, f).0
+    }
+
+    /// Creates a sub-[Ui] that shares the same bounds as the parent [Ui].
+    ///
+    /// Changes to the sub-[Ui]'s layout are reflected in the parent.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.sub_ui(|sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn sub_ui<F>(&mut self, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let (res, placer) = self.child_ui(self.bounds, self.placer.clone(), f);
+        self.placer = placer;
+
+        res
+    }
+
+    /// Creates a sub-[Ui] that places all its widgets in one row, so they can be handled as one widget.
+    ///
+    /// Inside of it, [Ui::add] (and the like) don't start a new row, but add spacing like [Ui::add_horizontal].
+    /// Wrapping is disabled, so widgets that don't fit into the row result in [GuiError::NoSpaceLeft].
+    /// Like [Ui::add], a new row is started afterwards.
+    ///
+    /// With [HorizontalAlign::Center] or [HorizontalAlign::Right], the row is aligned as a whole, and its
+    /// widgets keep the order they're added in. For that, `f` is called twice: first to measure the widgets
+    /// (using [Widget::measure], nothing is drawn and the responses are empty), then to draw them. So `f` must
+    /// add the same widgets both times, and if they use smartstates, use [Ui::horizontal_with_smartstates].
+    /// If a widget can't be measured, the row is placed like with [HorizontalAlign::Left].
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] combining the responses of all widgets added to the sub-[Ui] (see [Response::union]):
+    /// its area covers all of them, and it's clicked, down or changed if any of them is.
+    /// If `f` returns an error, it's set as the response's error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # use kolibri_embedded_gui::button::Button;
+    /// let mut volume = 5;
+    /// let response = ui.horizontal(|ui| {
+    ///     ui.add(Label::new("Volume"));
+    ///     if ui.add(Button::new("-")).clicked() {
+    ///         volume -= 1;
+    ///     }
+    ///     if ui.add(Button::new("+")).clicked() {
+    ///         volume += 1;
+    ///     }
+    ///     Ok(())
+    /// });
+    /// if response.clicked() {
+    ///     // save the volume
+    /// }
+    /// ```
+    pub fn horizontal<F>(&mut self, mut f: F) -> Response
+    where
+        F: FnMut(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let wrap = self.placer.wrap;
+        let align = self.placer.align;
+        let mut placer = self.placer.clone();
+        placer.set_wrap(false);
+        if let Some(offset) = self.measure_row(&placer, &mut f) {
+            // the row is drawn from left to right, starting where it ends up as a whole
+            placer.pos.x += offset as i32;
+            placer.set_align(Align(HorizontalAlign::Left, align.1));
+        }
+
+        let (res, mut placer) = self.child_ui(self.bounds, placer, |ui| {
+            let empty = || Response::new(InternalResponse::empty()).set_redraw(false);
+            ui.combined = Some(empty());
+            let res = f(ui);
+            let combined = ui.combined.take().unwrap_or_else(empty);
+            Ok(match res {
+                Ok(()) => combined,
+                Err(e) => combined.set_error(e),
+            })
+        });
+        placer.set_wrap(wrap);
+        placer.set_align(align);
+        self.placer = placer;
+
+        let resp = res.unwrap_or_else(Response::from_error);
+        self.combine(&resp);
+        self.end_widget();
+        resp
+    }
+
+    /// Like [Ui::horizontal], but rewinds the given [SmartstateProvider] before each call of `f`,
+    /// so the widgets get the same smartstates when the row is measured and drawn.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] combining the responses of all widgets added to the sub-[Ui] (see [Ui::horizontal]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+    /// # let mut smartstates = SmartstateProvider::<20>::new();
+    /// // status text in the top right corner
+    /// ui.set_align(Align(HorizontalAlign::Right, VerticalAlign::Top));
+    /// ui.horizontal_with_smartstates(&mut smartstates, |ui, smartstates| {
+    ///     ui.add(Label::new("12:00").smartstate(smartstates.nxt()));
+    ///     ui.add(Label::new("Battery 80%").smartstate(smartstates.nxt()));
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn horizontal_with_smartstates<F, const N: usize>(
+        &mut self,
+        smartstates: &mut SmartstateProvider<N>,
+        mut f: F,
+    ) -> Response
+    where
+        F: FnMut(&mut Ui<DRAW, COL>, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        let start = smartstates.get_pos();
+        self.horizontal(|ui| {
+            smartstates.restart_counter();
+            smartstates.skip(start);
+            f(ui, smartstates)
+        })
+    }
+
+    /// Measure pass of [Ui::horizontal]: calls `f` without drawing anything, to find out how wide the row is.
+    ///
+    /// ## Returns
+    ///
+    /// How far the row has to be moved from the position of `placer` to be centered / right-aligned as a whole,
+    /// or `None` if it's placed as it is (e.g. because it's left-aligned, or a widget can't be measured).
+    fn measure_row<F>(&mut self, placer: &Placer, f: &mut F) -> Option<u32>
+    where
+        F: FnMut(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        if placer.flow != FlowDirection::Horizontal
+            || matches!(placer.align.0, HorizontalAlign::Left)
+            || self.measuring.is_some()
+        {
+            return None;
+        }
+
+        let mut measuring = placer.clone();
+        measuring.set_align(Align(HorizontalAlign::Left, placer.align.1));
+        measuring.extent = Size::zero();
+        // the measure pass doesn't move the focus or queue overlays
+        let (focus, focus_index, focus_target) = (self.focus, self.focus_index, self.focus_target);
+        let overlays = self.painter.overlays.clone();
+        let queued_overlays = self.painter.queued_overlays.clone();
+        let (res, measured) = self.child_ui(self.bounds, measuring, |ui| {
+            ui.measuring = Some(true);
+            ui.combined = Some(Response::new(InternalResponse::empty()));
+            ui.interact = Interaction::None;
+            ui.painter.clip_to(Rectangle::zero());
+            f(ui)?;
+            Ok(ui.measuring == Some(true))
+        });
+        (self.focus, self.focus_index, self.focus_target) = (focus, focus_index, focus_target);
+        self.painter.overlays = overlays;
+        self.painter.queued_overlays = queued_overlays;
+        if !res.unwrap_or(false) {
+            return None;
+        }
+
+        let start = placer.pos.x as u32;
+        let width = measured.extent.width.saturating_sub(start);
+        let free = placer.row_end.saturating_sub(start).saturating_sub(width);
+        match placer.align.0 {
+            HorizontalAlign::Left => None,
+            HorizontalAlign::Center => Some(free / 2),
+            HorizontalAlign::Right => Some(free),
+        }
+    }
+
+    /// Adds a response to the combined response, if this is the [Ui] of [Ui::horizontal]
+    fn combine(&mut self, response: &Response) {
+        if let Some(combined) = self.combined.take() {
+            self.combined = Some(combined.union(response));
+        }
+    }
+
+    /// Creates a sub-[Ui] that places its widgets in columns instead of rows.
+    ///
+    /// Widgets added to the sub-[Ui] are stacked downwards, starting at the current position
+    /// of this [Ui]. When the remaining height runs out, placement wraps to a new column to the right.
+    /// [Ui::add] only adds spacing after a widget instead of starting a new row,
+    /// and [Ui::new_row] starts a new column.
+    ///
+    /// The alignment is inherited: the vertical alignment places widgets within their column,
+    /// and the horizontal alignment places them across the column. With [HorizontalAlign::Left],
+    /// widgets get the full width of the column (like [VerticalAlign::Top] does for rows).
+    ///
+    /// Afterwards, the space used by the columns is allocated in this [Ui] at the position the columns
+    /// were started at, regardless of the alignment of this [Ui].
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.vertical(|ui| {
+    ///     ui.add(Label::new("Speed"));
+    ///     ui.add(Label::new("Direction"));
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn vertical<F>(&mut self, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let bounds = self.free_area();
+        let mut placer = Placer::new_with_flow(
+            bounds.size,
+            true,
+            self.placer.align,
+            FlowDirection::Vertical,
+        );
+        placer.set_shrink_to_fit(self.placer.shrink_to_fit);
+
+        let (res, placer) = self.child_ui(bounds, placer, f);
+
+        // allocate the used space where the columns were drawn
+        self.allocate_at_cursor(placer.used_size())?;
+
+        res
+    }
+
+    /// Creates a [Grid] with the given columns at the current position of the [Ui].
+    ///
+    /// Each widget added to the grid is placed in the next cell, and each row is as tall as its tallest cell.
+    /// Column widths are either fixed, or a fraction of the width left in the current row. See the [grid module](crate::grid) for details.
+    ///
+    /// Like [Ui::add], a new row is started after the grid.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// # use kolibri_embedded_gui::grid::GridColumn;
+    /// ui.grid(&[GridColumn::Fraction(1, 2), GridColumn::Fraction(1, 2)], |grid| {
+    ///     grid.add(Label::new("Voltage"));
+    ///     grid.add(Label::new("3.3 V"));
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn grid<F>(&mut self, columns: &[GridColumn], f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Grid<'_, '_, DRAW, COL>) -> GuiResult<()>,
+    {
+        let mut grid = Grid::new(self, columns);
+        let res = f(&mut grid);
+        let used = grid.used_size();
+
+        self.allocate_at_cursor(used)?;
+        self.new_row();
+        res
+    }
+
+    /// Creates a [FlexRow] at the current position of the [Ui], sharing the available width between its children.
+    ///
+    /// Each child has a fixed width, its natural width, or a weight. The width that's left over after the
+    /// fixed and natural widths is shared between the weighted children. See the [flex module](crate::flex) for details.
+    ///
+    /// **Note:** `f` is called twice, once to measure the children and once to draw them.
+    /// Use [Ui::flex_row_with_smartstates] if the children use smartstates.
+    ///
+    /// Like [Ui::add], a new row is started afterwards.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// # use kolibri_embedded_gui::button::Button;
+    /// # use kolibri_embedded_gui::flex::FlexSize;
+    /// ui.flex_row(|row| {
+    ///     row.add(Label::new("Title"), FlexSize::Weight(1));
+    ///     row.add(Button::new("Close"), FlexSize::Auto);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn flex_row<F>(&mut self, mut f: F) -> GuiResult<()>
+    where
+        F: FnMut(&mut FlexRow<'_, '_, DRAW, COL>) -> GuiResult<()>,
+    {
+        let mut row = FlexRow::new(self);
+        f(&mut row)?;
+        row.start_drawing();
+        let res = f(&mut row);
+        let used = row.used_size();
+
+        self.allocate_at_cursor(used)?;
+        self.new_row();
+        res
+    }
+
+    /// Like [Ui::flex_row], but rewinds the given [SmartstateProvider] between the measure and the draw pass,
+    /// so the children get the same smartstates in both passes.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// # use kolibri_embedded_gui::button::Button;
+    /// # use kolibri_embedded_gui::flex::FlexSize;
+    /// # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+    /// # let mut smartstates = SmartstateProvider::<20>::new();
+    /// ui.flex_row_with_smartstates(&mut smartstates, |row, smartstates| {
+    ///     row.add(Label::new("Title").smartstate(smartstates.nxt()), FlexSize::Weight(1));
+    ///     row.add(Button::new("Close").smartstate(smartstates.nxt()), FlexSize::Auto);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn flex_row_with_smartstates<F, const N: usize>(
+        &mut self,
+        smartstates: &mut SmartstateProvider<N>,
+        mut f: F,
+    ) -> GuiResult<()>
+    where
+        F: FnMut(&mut FlexRow<'_, '_, DRAW, COL>, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        let start = smartstates.get_pos();
+        self.flex_row(|row| {
+            if !row.is_measuring() {
+                smartstates.restart_counter();
+                smartstates.skip(start);
+            }
+            f(row, smartstates)
+        })
+    }
+
+    /// Returns the current interaction (in the coordinates of the [Ui]).
+    pub(crate) fn interaction(&self) -> Interaction {
+        self.interact
+    }
+
+    /// Returns the (absolute) bounds of the [Ui], excluding the window border padding.
+    pub(crate) fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    /// Runs `f` on a child [Ui] covering the same bounds as this one, in which nothing is drawn inside of the
+    /// (absolute) `excluded` areas, e.g. because other windows are on top of them. If `interact` is false,
+    /// the child doesn't get the current interaction.
+    ///
+    /// The child starts with an empty placer, and doesn't affect the layout of this [Ui].
+    pub(crate) fn layer_ui<F, R>(
+        &mut self,
+        excluded: &[Rectangle],
+        interact: bool,
+        f: F,
+    ) -> GuiResult<R>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<R>,
+    {
+        let placer = Placer::new(self.bounds.size, true, self.placer.align);
+        self.child_ui(self.bounds, placer, |ui| {
+            for area in excluded {
+                ui.painter.exclude(*area)?;
+            }
+            if !interact {
+                ui.interact = Interaction::None;
+            }
+            f(ui)
+        })
+        .0
+    }
+
+    /// Mirrors the (absolute) `rect` horizontally within `outer` if the layout is right-to-left,
+    /// e.g. for containers that lay out their cells themselves.
+    pub(crate) fn mirrored(&self, outer: Rectangle, rect: Rectangle) -> Rectangle {
+        match self.placer.direction {
+            LayoutDirection::LeftToRight => rect,
+            LayoutDirection::RightToLeft => {
+                let right = outer.top_left.x + outer.size.width as i32;
+                let x = right - (rect.top_left.x - outer.top_left.x) - rect.size.width as i32;
+                Rectangle::new(Point::new(x, rect.top_left.y), rect.size)
+            }
+        }
+    }
+
+    /// Returns the (absolute) free area, starting at the current position of the placer.
+    pub(crate) fn free_area(&self) -> Rectangle {
+        self.placer.free_rect().translate(self.bounds.top_left)
+    }
+
+    /// Allocates `size` at the current position of the placer (the top left corner of [Ui::free_area]),
+    /// ignoring the alignment and without wrapping.
+    ///
+    /// Used by containers that draw their content first and allocate the space it took afterwards.
+    /// Allocating a zero size does nothing.
+    pub(crate) fn allocate_at_cursor(&mut self, size: Size) -> GuiResult<Rectangle> {
+        if size == Size::zero() {
+            let cursor = self.placer.mirror(Rectangle::new(self.placer.pos, size));
+            return Ok(cursor.translate(self.bounds.top_left));
+        }
+        let align = self.placer.align;
+        self.placer.set_align(Align::default());
+        let alloc = self.placer.next_no_wrap(size);
+        self.placer.set_align(align);
+        alloc.map(|mut rect| {
+            rect.top_left.add_assign(self.bounds.top_left);
+            rect
+        })
+    }
+
+    /// Runs `f` on a child [Ui] covering exactly the given (absolute) `bounds`, without any padding.
+    ///
+    /// The child starts with an empty placer, using the alignment and shrink to fit setting of this [Ui].
+    ///
+    /// ## Returns
+    ///
+    /// The result of `f`, together with the size used by the widgets placed in the child.
+    pub(crate) fn area_ui<F, R>(&mut self, bounds: Rectangle, f: F) -> (GuiResult<R>, Size)
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<R>,
+    {
+        let mut placer = Placer::new(bounds.size, true, self.placer.align);
+        placer.set_shrink_to_fit(self.placer.shrink_to_fit);
+        let (res, placer) = self.child_ui(bounds, placer, f);
+        (res, placer.used_size())
+    }
+
+    /// Like [Ui::area_ui], but only the part of `bounds` inside `clip` (absolute) is drawn to and can be interacted with.
+    pub(crate) fn clipped_area_ui<F, R>(
+        &mut self,
+        bounds: Rectangle,
+        clip: Rectangle,
+        f: F,
+    ) -> (GuiResult<R>, Size)
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<R>,
+    {
+        self.area_ui(bounds, |ui| {
+            ui.painter.clip_to(clip);
+            f(ui)
+        })
+    }
+
+    /// Runs `f` on a child [Ui] with the given (absolute) `bounds` and `placer`, drawing with this [Ui]'s painter.
+    ///
+    /// ## Returns
+    ///
+    /// The result of `f`, together with the child's placer after `f` ran (e.g. to find out how much space was used).
+    /// If `f` fails, the placer is still returned as far as it got.
+    fn child_ui<F, R>(&mut self, bounds: Rectangle, placer: Placer, f: F) -> (GuiResult<R>, Placer)
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<R>,
+    {
+        // the child doesn't hand the consumed focus interactions back
+        self.navigate_focus();
+        let mut placer = placer;
+        placer.set_direction(self.placer.direction);
+        let mut overlays = self.painter.overlays.clone();
+        let mut queued_overlays = self.painter.queued_overlays.clone();
+        let res = self.painter.with_subpainter(|painter| {
+            let mut sub_ui = Ui {
+                painter,
+                bounds,
+                style: self.style,
+                interact: self.interact,
+                placer: placer.clone(),
+                cleared: false,
+                debug_color: self.debug_color,
+                margins: None,
+                combined: None,
+                measuring: self.measuring,
+                focus: self.focus,
+                focus_index: self.focus_index,
+                focus_target: self.focus_target,
+            };
+            let res = (f)(&mut sub_ui);
+            placer = sub_ui.placer;
+            // focusable widgets are numbered across all child uis
+            self.focus = sub_ui.focus;
+            self.focus_index = sub_ui.focus_index;
+            self.focus_target = sub_ui.focus_target;
+            if self.measuring.is_some() {
+                self.measuring = sub_ui.measuring;
+            }
+            // overlays queued in the child cover the widgets added to this ui afterwards, too
+            overlays = sub_ui.painter.overlays;
+            queued_overlays = sub_ui.painter.queued_overlays;
+            res
+        });
+        self.painter.overlays = overlays;
+        self.painter.queued_overlays = queued_overlays;
+        (res, placer)
+    }
+
+    /// Creates a right-side panel sub-[Ui] with the specified width.
+    ///
+    /// If `allow_smaller` is false, an error is returned if there is insufficient space.
+    /// See [Ui::panel_ui] for details.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.right_panel_ui(100, false, |sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn right_panel_ui<F>(&mut self, width: u32, allow_smaller: bool, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.panel_ui(PanelSide::Right, width, allow_smaller, false, f)
+    }
+
+    /// Creates a left-side panel sub-[Ui] with the specified width.
+    ///
+    /// If `allow_smaller` is false, an error is returned if there is insufficient space.
+    /// See [Ui::panel_ui] for details.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.left_panel_ui(80, false, |sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn left_panel_ui<F>(&mut self, width: u32, allow_smaller: bool, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.panel_ui(PanelSide::Left, width, allow_smaller, false, f)
+    }
+
+    /// Creates a top panel sub-[Ui] with the specified height.
+    ///
+    /// If `allow_smaller` is false, an error is returned if there is insufficient space.
+    /// See [Ui::panel_ui] for details.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.top_panel_ui(30, false, |sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn top_panel_ui<F>(&mut self, height: u32, allow_smaller: bool, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.panel_ui(PanelSide::Top, height, allow_smaller, false, f)
+    }
+
+    /// Creates a bottom panel sub-[Ui] with the specified height.
+    ///
+    /// If `allow_smaller` is false, an error is returned if there is insufficient space.
+    /// See [Ui::panel_ui] for details.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.bottom_panel_ui(30, false, |sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn bottom_panel_ui<F>(&mut self, height: u32, allow_smaller: bool, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        self.panel_ui(PanelSide::Bottom, height, allow_smaller, false, f)
+    }
+
+    /// Creates a panel sub-[Ui] docked to the given `side` of this [Ui].
+    ///
+    /// Left and right panels are `size` pixels wide, and reach from the current row down to the bottom.
+    /// Top and bottom panels are `size` pixels high, and span the full width. A top panel starts at the
+    /// current row, so widgets added before it stay above it. If the current row already has widgets,
+    /// top panels and panels on the side the row starts at begin on a new row instead, so they don't cover them.
+    ///
+    /// The bounds of this [Ui] shrink by the panel, so widgets added afterwards are placed next to it,
+    /// and further panels are docked next to it (e.g. a left navigation rail below a top header bar).
+    /// Panels should be added before the widgets in the area they cover.
+    ///
+    /// If `allow_smaller` is false, an error is returned if there is insufficient space.
+    /// Otherwise, the panel is shrunk to the space that's left.
+    ///
+    /// By default, the panel is placed inside this [Ui]'s `window_border_padding`, and its content
+    /// gets its own `window_border_padding` (like [Ui::unchecked_sub_ui]). If `flush` is true, the
+    /// panel covers the border padding of this [Ui] on its side instead, and its content isn't padded,
+    /// so a panel in a fullscreen [Ui] sits flush with the edges of the screen. The remaining area of
+    /// this [Ui] keeps its border padding towards the panel.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// // header bar along the top edge of the screen
+    /// ui.panel_ui(PanelSide::Top, 30, false, true, |header| {
+    ///     header.add(Label::new("Settings"));
+    ///     Ok(())
+    /// }).unwrap();
+    ///
+    /// // navigation rail below the header
+    /// ui.panel_ui(PanelSide::Left, 60, false, true, |rail| {
+    ///     rail.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn panel_ui<F>(
+        &mut self,
+        side: PanelSide,
+        size: u32,
+        allow_smaller: bool,
+        flush: bool,
+        f: F,
+    ) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let area = self.dock_panel(side, size, allow_smaller, flush)?;
+
+        if flush {
+            let placer = Placer::new(
+                area.size,
+                true,
+                Align(HorizontalAlign::Left, VerticalAlign::Top),
+            );
+            self.child_ui(area, placer, f).0
+        } else {
+            self.unchecked_sub_ui(area, f)
+        }
+    }
+
+    /// Takes the area of a panel docked to `side` from the bounds of this [Ui] (see [Ui::panel_ui]).
+    ///
+    /// ## Returns
+    ///
+    /// The (absolute) area of the panel, or an error if it doesn't fit and `allow_smaller` is false.
+    fn dock_panel(
+        &mut self,
+        side: PanelSide,
+        size: u32,
+        allow_smaller: bool,
+        flush: bool,
+    ) -> GuiResult<Rectangle> {
+        // a panel on top, or at the start of the current row, would cover the widgets in the row
+        let row_start = match self.placer.direction {
+            LayoutDirection::LeftToRight => PanelSide::Left,
+            LayoutDirection::RightToLeft => PanelSide::Right,
+        };
+        if self.placer.flow == FlowDirection::Horizontal
+            && self.placer.pos.x > 0
+            && (side == PanelSide::Top || side == row_start)
+        {
+            self.new_row();
+        }
+
+        let inner = self.bounds;
+        let pos = self.placer.pos;
+        let (gap, outer) = if flush {
+            let pad = self.style.spacing.window_border_padding;
+            (
+                pad,
+                Rectangle::new(inner.top_left - pad, inner.size.saturating_add(pad * 2)),
+            )
+        } else {
+            (Size::zero(), inner)
+        };
+
+        // the panel doesn't cover widgets in the rows above (but reaches the edge if there are none)
+        let top = if pos.y > 0 {
+            inner.top_left.y + pos.y
+        } else {
+            outer.top_left.y
+        };
+        let region = Rectangle::new(
+            Point::new(outer.top_left.x, top),
+            Size::new(
+                outer.size.width,
+                (outer.size.height as i32 - (top - outer.top_left.y)).max(0) as u32,
+            ),
+        );
+
+        let max_size = match side {
+            PanelSide::Left | PanelSide::Right => region.size.width.saturating_sub(pos.x as u32),
+            PanelSide::Top | PanelSide::Bottom => region.size.height,
+        };
+        if size > max_size && !allow_smaller {
+            return Err(GuiError::BoundsError);
+        }
+        let size = min(size, max_size);
+
+        let inner_end = inner.top_left + inner.size;
+        let region_end = region.top_left + region.size;
+        let (area, start, end) = match side {
+            PanelSide::Left => (
+                Rectangle::new(region.top_left, Size::new(size, region.size.height)),
+                Size::new(
+                    (region.top_left.x + (size + gap.width) as i32 - inner.top_left.x).max(0)
+                        as u32,
+                    0,
+                ),
+                Size::zero(),
+            ),
+            PanelSide::Right => (
+                Rectangle::new(
+                    Point::new(region_end.x - size as i32, region.top_left.y),
+                    Size::new(size, region.size.height),
+                ),
+                Size::zero(),
+                Size::new(
+                    (inner_end.x - (region_end.x - (size + gap.width) as i32)).max(0) as u32,
+                    0,
+                ),
+            ),
+            PanelSide::Top => (
+                Rectangle::new(region.top_left, Size::new(region.size.width, size)),
+                Size::new(
+                    0,
+                    (region.top_left.y + (size + gap.height) as i32 - inner.top_left.y).max(0)
+                        as u32,
+                ),
+                Size::zero(),
+            ),
+            PanelSide::Bottom => (
+                Rectangle::new(
+                    Point::new(region.top_left.x, region_end.y - size as i32),
+                    Size::new(region.size.width, size),
+                ),
+                Size::zero(),
+                Size::new(
+                    0,
+                    (inner_end.y - (region_end.y - (size + gap.height) as i32)).max(0) as u32,
+                ),
+            ),
+        };
+
+        self.placer.shrink(start, end);
+        self.bounds = Rectangle::new(
+            inner.top_left + start,
+            inner.size.saturating_sub(start + end),
+        );
+
+        Ok(area)
+    }
+
+    /// Creates a centered sub-[Ui] panel with the specified width and height.
+    ///
+    /// The panel is centered within the current bounds. An error is returned if the dimensions exceed the available space.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// ui.central_centered_panel_ui(200, 150, |sub_ui| {
+    ///     sub_ui.add(widget);
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn central_centered_panel_ui<F>(&mut self, width: u32, height: u32, f: F) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let bounds = self.placer.bounds;
+        let max_width = bounds.width;
+        let max_height = bounds.height;
+
+        if width > max_width {
+            return Err(GuiError::BoundsError);
+        }
+
+        if height > max_height {
+            return Err(GuiError::BoundsError);
+        }
+
+        self.placer.bounds.width -= min(width, max_width);
+        self.placer.bounds.height -= min(height, max_height);
+
+        let area = Rectangle::new(
+            Point::new(
+                ((bounds.width - width) / 2) as i32,
+                ((bounds.height - height) / 2) as i32,
+            ),
+            Size::new(width, height),
+        );
+
+        self.unchecked_sub_ui(area, f)
+    }
+
+    /// Shows a modal dialog with the specified width and height, centered within the [Ui]'s bounds,
+    /// while `open` is `true`.
+    ///
+    /// The background is covered, and a framed dialog is drawn on top of it. The dialog's content is
+    /// added in `f`. Afterwards, the rest of the [Ui] is neither drawn to nor interacted with for this
+    /// frame, so all interactions outside of the dialog are swallowed.
+    ///
+    /// Because of this, the modal must be shown **before** the widgets behind it, and it should be called
+    /// every frame (also while the dialog is closed). `smartstate` keeps track of whether the dialog is open:
+    /// the background and frame are only drawn when it opens (or the [Ui] was cleared), and the [Ui]'s area
+    /// is cleared when it closes, so the widgets behind it are drawn on an empty background again.
+    /// Widgets behind the dialog that use smartstates aren't forced to redraw though, use
+    /// [Ui::modal_with_smartstates] for them.
+    ///
+    /// An error is returned if the dimensions exceed the [Ui]'s bounds.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// # use kolibri_embedded_gui::button::Button;
+    /// # use kolibri_embedded_gui::smartstate::Smartstate;
+    /// # let mut erase_open = true;
+    /// // kept between frames
+    /// let mut modal_state = Smartstate::empty();
+    ///
+    /// ui.modal(erase_open, 200, 100, &mut modal_state, |ui| {
+    ///     ui.add(Label::new("Really erase calibration?"));
+    ///     if ui.add_horizontal(Button::new("Erase")).clicked() {
+    ///         // ...
+    ///         erase_open = false;
+    ///     }
+    ///     if ui.add_horizontal(Button::new("Cancel")).clicked() {
+    ///         erase_open = false;
+    ///     }
+    ///     Ok(())
+    /// }).unwrap();
+    /// // widgets behind the dialog
+    /// ui.add(widget);
+    /// ```
+    pub fn modal<F>(
+        &mut self,
+        open: bool,
+        width: u32,
+        height: u32,
+        smartstate: &mut Smartstate,
+        f: F,
+    ) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>) -> GuiResult<()>,
+    {
+        let changed = self.update_modal(open, smartstate)?;
+        if !open {
+            return Ok(());
+        }
+
+        let area = self.modal_area(width, height)?;
+        if changed || self.cleared {
+            self.draw_modal_frame(area)?;
+        }
+        let res = self.unchecked_sub_ui(area, f);
+        self.capture_modal_input();
+        res
+    }
+
+    /// Like [Ui::modal], but the dialog's content uses smartstates from `smartstates`.
+    ///
+    /// The modal takes one smartstate for itself, which it uses to track whether the dialog is open.
+    /// When the dialog opens, the background and frame are drawn once, and all following smartstates are
+    /// forced to redraw. When it closes, the [Ui]'s area is cleared and all following smartstates are forced
+    /// to redraw, so the widgets behind the dialog are restored.
+    ///
+    /// Like [Ui::modal], it must be called **before** the widgets behind the dialog, and it should be called
+    /// every frame (also while the dialog is closed).
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// # let mut widget = Label::new("Hi");
+    /// # use kolibri_embedded_gui::button::Button;
+    /// # use kolibri_embedded_gui::smartstate::SmartstateProvider;
+    /// # let mut smartstates = SmartstateProvider::<20>::new();
+    /// # let mut erase_open = true;
+    /// ui.modal_with_smartstates(erase_open, 200, 100, &mut smartstates, |ui, smartstates| {
+    ///     ui.add(Label::new("Really erase calibration?").smartstate(smartstates.nxt()));
+    ///     if ui.add(Button::new("Cancel").smartstate(smartstates.nxt())).clicked() {
+    ///         erase_open = false;
+    ///     }
+    ///     Ok(())
+    /// }).unwrap();
+    /// // widgets behind the dialog
+    /// ui.add(Label::new("Calibration").smartstate(smartstates.nxt()));
+    /// ```
+    pub fn modal_with_smartstates<F, const N: usize>(
+        &mut self,
+        open: bool,
+        width: u32,
+        height: u32,
+        smartstates: &mut SmartstateProvider<N>,
+        f: F,
+    ) -> GuiResult<()>
+    where
+        F: FnOnce(&mut Ui<DRAW, COL>, &mut SmartstateProvider<N>) -> GuiResult<()>,
+    {
+        let changed = self.update_modal(open, smartstates.nxt())?;
+        if changed {
+            // the widgets behind the dialog are covered or restored
+            smartstates.force_redraw_remaining();
+        }
+        if !open {
+            return Ok(());
+        }
+
+        let area = self.modal_area(width, height)?;
+        if changed || self.cleared {
+            self.draw_modal_frame(area)?;
+        }
+        let res = self.unchecked_sub_ui(area, |ui| f(ui, smartstates));
+        self.capture_modal_input();
+        res
+    }
+
+    /// Keeps track of whether a modal dialog is `open` in `smartstate`, clearing the [Ui]'s area when it closes.
+    ///
+    /// ## Returns
+    ///
+    /// Whether the dialog was opened or closed this frame.
+    fn update_modal(&mut self, open: bool, smartstate: &mut Smartstate) -> GuiResult<bool> {
+        let state = Smartstate::state(open as u32);
+        let changed = *smartstate != state;
+        *smartstate = state;
+        if changed && !open {
+            self.clear_area(self.bounds)?;
+        }
+        Ok(changed)
+    }
+
+    /// Returns the (absolute) area of a modal dialog with the given size, centered within the [Ui]'s bounds
+    fn modal_area(&self, width: u32, height: u32) -> GuiResult<Rectangle> {
+        let size = Size::new(width, height);
+        if width > self.bounds.size.width || height > self.bounds.size.height {
+            return Err(GuiError::BoundsError);
+        }
+        let offset = (self.bounds.size - size) / 2;
+        Ok(Rectangle::new(
+            self.bounds.top_left + Point::new(offset.width as i32, offset.height as i32),
+            size,
+        ))
+    }
+
+    /// Covers the [Ui]'s area with the background color, and draws the frame of a modal dialog at `area`
+    fn draw_modal_frame(&mut self, area: Rectangle) -> GuiResult<()> {
+        self.clear_area(self.bounds)?;
+        let frame_style = PrimitiveStyleBuilder::new()
+            .stroke_color(self.style.highlight_border_color)
+            .stroke_width(self.style.highlight_border_width)
+            .fill_color(self.style.background_color)
+            .build();
+        self.draw(&area.into_styled(frame_style))
+            .map_err(|_| GuiError::DrawError(Some("Couldn't draw modal frame")))
+    }
+
+    /// Makes the rest of the [Ui] ignore all interactions and drawing for this frame
+    fn capture_modal_input(&mut self) {
+        self.interact = Interaction::None;
+        self.painter.clip_to(Rectangle::zero());
+    }
+
+    /// Queues an overlay (e.g. a dropdown list or a tooltip) at the given (absolute) area, to be drawn at the end
+    /// of the frame by [Ui::finish].
+    ///
+    /// Overlays are kept in a fixed-size queue, so instead of a closure with its content, an overlay is described by
+    /// its area and an `id` chosen by the caller (e.g. which popup it is). [Ui::finish] calls back with the id to add
+    /// the content. This way, an overlay is on top of all widgets in the frame, including the ones added after it
+    /// (in this [Ui] or any of its parents), and later overlays are on top of earlier ones. Overlays aren't clipped
+    /// by the containers they're queued in (e.g. a [ScrollArea](crate::scroll_area::ScrollArea)).
+    ///
+    /// From the moment it's queued, the overlay's area is blocked for the following widgets: they aren't drawn
+    /// inside of it (so they don't flicker below the overlay), and don't get interactions there.
+    /// Widgets added *before* the overlay in the same frame can still draw below it and get its interactions.
+    /// To prevent that, pass the areas returned by [Ui::finish] to [Ui::set_previous_overlays] in the next frame.
+    /// Those areas take precedence over the widgets from the start of the frame, so the overlay gets the
+    /// interactions inside of it. The area of an overlay that wasn't shown in the previous frame is cleared
+    /// before its content is added.
+    ///
+    /// In turn, the area of an overlay that's closed (i.e. not queued anymore) still blocks the widgets below it
+    /// in the frame it's closed in. When closing a popup, callers should:
+    /// - remove its area from the [OverlayAreas] with [OverlayAreas::remove] before passing them to
+    ///   [Ui::set_previous_overlays], if it's closed before the frame starts (e.g. by the previous frame's interaction)
+    /// - otherwise, redraw the widgets below it in the next frame, where its area isn't blocked anymore
+    ///   (e.g. with [SmartstateProvider::force_redraw_all]). Interactions inside of its area are lost in the frame
+    ///   it's closed in.
+    ///
+    /// ## Returns
+    ///
+    /// An error if more than 8 overlays are queued in a frame.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// const TOOLTIP: usize = 0;
+    ///
+    /// let mut overlays = OverlayAreas::new();
+    /// loop {
+    ///     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    ///     ui.set_previous_overlays(&overlays);
+    ///
+    ///     if ui.add(Label::new("Hover me")).internal.interaction != Interaction::None {
+    ///         let tooltip = Rectangle::new(Point::new(10, 30), Size::new(100, 24));
+    ///         ui.overlay(tooltip, TOOLTIP).unwrap();
+    ///     }
+    ///     // below the tooltip
+    ///     ui.add(Label::new("Below"));
+    ///
+    ///     overlays = ui
+    ///         .finish(|ui, id| {
+    ///             if id == TOOLTIP {
+    ///                 ui.add(Label::new("Tooltip"));
+    ///             }
+    ///             Ok(())
+    ///         })
+    ///         .unwrap();
+    ///     # break;
+    /// }
+    /// ```
+    pub fn overlay(&mut self, area: Rectangle, id: usize) -> GuiResult<()> {
+        self.painter
+            .queued_overlays
+            .push(id)
+            .map_err(|_| GuiError::draw_error("Too many overlays"))?;
+        // there's room for the overlays of this frame (checked above)
+        self.painter.overlays.push(area).ok();
+        Ok(())
+    }
+
+    /// Sets the overlay areas of the previous frame (see [Ui::finish]), which take precedence over all widgets
+    /// from the start of this frame: widgets aren't drawn inside of them, and don't get interactions there
+    /// (overlays do, though).
+    ///
+    /// This should be called right after creating the [Ui], before adding any widgets. It doesn't matter
+    /// whether it's called before or after [Ui::interact], as the focus is only moved once the first
+    /// focusable widget is added (or at the latest in [Ui::focus_state]).
+    /// If the overlay areas differ from the previous frame, the widgets that were below a removed overlay
+    /// need to be redrawn (e.g. with [SmartstateProvider::force_redraw_all]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// let mut overlays = OverlayAreas::new();
+    /// loop {
+    ///     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    ///     ui.set_previous_overlays(&overlays);
+    ///     // ... add widgets and queue overlays
+    ///     overlays = ui.finish(|ui, id| Ok(())).unwrap();
+    ///     # break;
+    /// }
+    /// ```
+    pub fn set_previous_overlays(&mut self, overlays: &OverlayAreas) {
+        let current = self.painter.overlays[self.painter.previous_overlays..].iter();
+        self.painter.overlays = overlays.areas.iter().chain(current).copied().collect();
+        self.painter.previous_overlays = overlays.areas.len();
+    }
+
+    /// Finishes the frame, drawing the overlays queued with [Ui::overlay].
+    ///
+    /// The overlays are drawn in the order they were queued, each in a sub-[Ui] covering its area, with `f`
+    /// adding the content of the overlay with the given id. Overlays queued by `f` are drawn after the others.
+    /// Dropping the [Ui] without calling this discards the queued overlays.
+    ///
+    /// ## Returns
+    ///
+    /// The areas of the overlays shown in this frame, to be passed to [Ui::set_previous_overlays] in the next frame,
+    /// or the first error returned by `f`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_graphics::pixelcolor::Rgb565;
+    /// # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+    /// # use kolibri_embedded_gui::style::medsize_rgb565_style;
+    /// # use kolibri_embedded_gui::ui::Ui;
+    /// # use embedded_graphics::prelude::*;
+    /// # use embedded_graphics::primitives::Rectangle;
+    /// # use embedded_iconoir::prelude::*;
+    /// # use kolibri_embedded_gui::ui::*;
+    /// # use kolibri_embedded_gui::label::*;
+    /// # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+    /// # let output_settings = OutputSettingsBuilder::new().build();
+    /// # let mut window = Window::new("Kolibri Example", &output_settings);
+    /// # let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+    /// let overlays = ui.finish(|ui, id| {
+    ///     ui.add(Label::new("Popup"));
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn finish<F>(&mut self, mut f: F) -> GuiResult<OverlayAreas>
+    where
+        F: FnMut(&mut Ui<DRAW, COL>, usize) -> GuiResult<()>,
+    {
+        let previous = self.painter.previous_overlays;
+        let mut drawn = 0;
+        while let Some(&id) = self.painter.queued_overlays.get(drawn) {
+            let area = self.painter.overlays[previous + drawn];
+            let new = !self.painter.overlays[..previous].contains(&area);
+            let placer = Placer::new(area.size, true, self.placer.align);
+
+            self.child_ui(area, placer, |ui| {
+                ui.painter.clip = None;
+                ui.painter.in_overlay = true;
+                if new {
+                    ui.clear_area(area)?;
+                }
+                f(ui, id)
+            })
+            .0?;
+            drawn += 1;
+        }
+
+        let areas = self.painter.overlays[previous..].iter().copied().collect();
+        Ok(OverlayAreas { areas })
+    }
+}
+
+// -- Debug drawing methods --
+impl<COL, DRAW> Ui<'_, DRAW, COL>
+where
+    DRAW: DrawTarget<Color = COL>,
+    COL: PixelColor,
+{
+    /// Draws a debug outline around the [Ui]'s bounds using the specified color.
+    ///
+    /// ## Returns
+    ///
+    /// A [GuiResult] indicating success or error.
     ///
     /// # Example
     ///
@@ -2035,3 +5152,1228 @@ where
         self.debug_color = Some(color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::button::Button;
+    use crate::checkbox::Checkbox;
+    use crate::icon::IconWidget;
+    use crate::iconbutton::IconButton;
+    use crate::label::{HashLabel, Hasher, Label};
+    use crate::separator::Separator;
+    use crate::slider::Slider;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use crate::toggle_button::ToggleButton;
+    use crate::toggle_switch::ToggleSwitch;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+    use embedded_iconoir::size12px;
+
+    fn placer(align: Align) -> Placer {
+        Placer::new(Size::new(100, 50), true, align)
+    }
+
+    #[test]
+    fn test_placer_left_top() {
+        let mut placer = placer(Align::default());
+        let first = placer.next(Size::new(20, 10)).unwrap();
+        let second = placer.next(Size::new(30, 16)).unwrap();
+        assert_eq!(first, Rectangle::new(Point::new(0, 0), Size::new(20, 10)));
+        assert_eq!(second, Rectangle::new(Point::new(20, 0), Size::new(30, 16)));
+    }
+
+    #[test]
+    fn test_placer_right() {
+        let mut placer = placer(Align(HorizontalAlign::Right, VerticalAlign::Top));
+        let first = placer.next(Size::new(20, 10)).unwrap();
+        let second = placer.next(Size::new(30, 10)).unwrap();
+        assert_eq!(first.top_left, Point::new(80, 0));
+        assert_eq!(second.top_left, Point::new(50, 0));
+        assert_eq!(placer.space_available(), Size::new(50, 50));
+    }
+
+    #[test]
+    fn test_placer_right_spacing() {
+        let mut placer = placer(Align(HorizontalAlign::Right, VerticalAlign::Top));
+        placer.next(Size::new(20, 10)).unwrap();
+        placer.space(Size::new(5, 5));
+        let second = placer.next(Size::new(30, 10)).unwrap();
+        assert_eq!(second.top_left, Point::new(45, 0));
+    }
+
+    #[test]
+    fn test_placer_wrap() {
+        let mut placer = placer(Align::default());
+        placer.set_spacing(Size::new(8, 4));
+        placer.next(Size::new(60, 10)).unwrap();
+        placer.next(Size::new(30, 16)).unwrap();
+
+        // starts at the left edge, below the tallest widget of the row plus the spacing
+        let wrapped = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(
+            wrapped,
+            Rectangle::new(Point::new(0, 20), Size::new(20, 10))
+        );
+
+        // the wrapped row continues normally
+        let next = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(next.top_left, Point::new(20, 20));
+    }
+
+    #[test]
+    fn test_placer_wrap_right_aligned() {
+        let mut placer = placer(Align(HorizontalAlign::Right, VerticalAlign::Top));
+        placer.set_spacing(Size::new(8, 4));
+        placer.next(Size::new(60, 10)).unwrap();
+        let wrapped = placer.next(Size::new(50, 10)).unwrap();
+        assert_eq!(wrapped.top_left, Point::new(50, 14));
+    }
+
+    #[test]
+    fn test_placer_wrap_out_of_space() {
+        let mut placer = placer(Align::default());
+        placer.set_spacing(Size::new(8, 4));
+        placer.next(Size::new(60, 30)).unwrap();
+        // the next row would start at y = 34, so a 20px high widget doesn't fit
+        assert_eq!(placer.next(Size::new(50, 20)), Err(GuiError::NoSpaceLeft));
+        // the row is left untouched
+        assert_eq!(
+            placer.next(Size::new(40, 10)).unwrap().top_left,
+            Point::new(60, 0)
+        );
+
+        let mut no_wrap = Placer::new(Size::new(100, 50), false, Align::default());
+        no_wrap.next(Size::new(60, 10)).unwrap();
+        assert_eq!(no_wrap.next(Size::new(50, 10)), Err(GuiError::NoSpaceLeft));
+    }
+
+    #[test]
+    fn test_placer_widget_too_large() {
+        let mut placer = placer(Align::default());
+        assert_eq!(
+            placer.next(Size::new(101, 10)),
+            Err(GuiError::WidgetTooLarge)
+        );
+        assert_eq!(
+            placer.next(Size::new(10, 51)),
+            Err(GuiError::WidgetTooLarge)
+        );
+        // a failed placement doesn't wrap
+        assert_eq!(
+            placer.next(Size::new(10, 10)).unwrap().top_left,
+            Point::zero()
+        );
+    }
+
+    #[test]
+    fn test_placer_shrink_to_fit() {
+        let mut placer = placer(Align::default());
+        placer.set_spacing(Size::new(8, 4));
+        placer.set_shrink_to_fit(true);
+
+        // widgets that can't shrink aren't affected
+        assert_eq!(
+            placer.next(Size::new(120, 10)),
+            Err(GuiError::WidgetTooLarge)
+        );
+
+        // wider than the whole row: shrunk to the row width
+        let first = placer.next_shrinkable(Size::new(120, 10)).unwrap();
+        assert_eq!(first, Rectangle::new(Point::zero(), Size::new(100, 10)));
+
+        // fits into the next row: wrapped, not shrunk
+        let second = placer.next_shrinkable(Size::new(60, 10)).unwrap();
+        assert_eq!(second, Rectangle::new(Point::new(0, 14), Size::new(60, 10)));
+
+        // without wrapping: shrunk to the rest of the row
+        placer.set_wrap(false);
+        let third = placer.next_shrinkable(Size::new(60, 10)).unwrap();
+        assert_eq!(third, Rectangle::new(Point::new(60, 14), Size::new(40, 10)));
+    }
+
+    #[test]
+    fn test_placer_center() {
+        let mut placer = placer(Align(HorizontalAlign::Center, VerticalAlign::Top));
+        let first = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(first.top_left, Point::new(40, 0));
+        // the next widget is centered in the remaining space
+        let second = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(second.top_left, Point::new(70, 0));
+    }
+
+    #[test]
+    fn test_placer_mixed_alignment_in_row() {
+        let mut placer = placer(Align::default());
+        placer.next(Size::new(20, 10)).unwrap();
+        placer.set_align(Align(HorizontalAlign::Right, VerticalAlign::Top));
+        let right = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(right.top_left, Point::new(80, 0));
+        placer.set_align(Align::default());
+        let left = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(left.top_left, Point::new(20, 0));
+        // only 40px are left between the left and right widgets
+        assert_eq!(placer.space_available().width, 40);
+        placer.set_wrap(false);
+        assert!(placer.next(Size::new(41, 10)).is_err());
+    }
+
+    #[test]
+    fn test_placer_vertical_alignment() {
+        let mut placer = placer(Align(HorizontalAlign::Left, VerticalAlign::Center));
+        placer.expand_row_height(20);
+        let center = placer.next(Size::new(10, 10)).unwrap();
+        assert_eq!(center, Rectangle::new(Point::new(0, 5), Size::new(10, 10)));
+        placer.set_align(Align(HorizontalAlign::Left, VerticalAlign::Bottom));
+        let bottom = placer.next(Size::new(10, 4)).unwrap();
+        assert_eq!(bottom, Rectangle::new(Point::new(10, 16), Size::new(10, 4)));
+    }
+
+    #[test]
+    fn test_placer_vertical_flow() {
+        let mut placer = Placer::new_with_flow(
+            Size::new(100, 50),
+            true,
+            Align::default(),
+            FlowDirection::Vertical,
+        );
+        let first = placer.next(Size::new(20, 20)).unwrap();
+        let second = placer.next(Size::new(30, 20)).unwrap();
+        assert_eq!(first, Rectangle::new(Point::new(0, 0), Size::new(20, 20)));
+        // left-aligned widgets get the full column width
+        assert_eq!(second, Rectangle::new(Point::new(0, 20), Size::new(30, 20)));
+        assert_eq!(placer.space_available(), Size::new(100, 10));
+
+        // doesn't fit into the column anymore, so it wraps to the next one
+        let third = placer.next(Size::new(10, 20)).unwrap();
+        assert_eq!(third, Rectangle::new(Point::new(30, 0), Size::new(10, 20)));
+        assert_eq!(placer.used_size(), Size::new(40, 40));
+    }
+
+    #[test]
+    fn test_placer_vertical_flow_alignment() {
+        let mut placer = Placer::new_with_flow(
+            Size::new(100, 50),
+            false,
+            Align(HorizontalAlign::Right, VerticalAlign::Bottom),
+            FlowDirection::Vertical,
+        );
+        placer.expand_row_height(30);
+        let first = placer.next(Size::new(20, 20)).unwrap();
+        let second = placer.next(Size::new(10, 20)).unwrap();
+        assert_eq!(first, Rectangle::new(Point::new(10, 30), Size::new(20, 20)));
+        assert_eq!(
+            second,
+            Rectangle::new(Point::new(20, 10), Size::new(10, 20))
+        );
+        assert!(placer.next(Size::new(10, 20)).is_err());
+    }
+
+    #[test]
+    fn test_placer_new_row_resets_alignment_cursors() {
+        let mut placer = placer(Align(HorizontalAlign::Right, VerticalAlign::Top));
+        placer.next(Size::new(20, 10)).unwrap();
+        placer.new_row(0);
+        let next = placer.next(Size::new(20, 10)).unwrap();
+        assert_eq!(next.top_left, Point::new(80, 10));
+    }
+
+    #[test]
+    fn test_placer_right_to_left() {
+        let mut placer = placer(Align::default());
+        placer.set_direction(LayoutDirection::RightToLeft);
+        placer.set_spacing(Size::new(8, 4));
+        let first = placer.next(Size::new(20, 10)).unwrap();
+        let second = placer.next(Size::new(30, 10)).unwrap();
+        assert_eq!(first, Rectangle::new(Point::new(80, 0), Size::new(20, 10)));
+        assert_eq!(second, Rectangle::new(Point::new(50, 0), Size::new(30, 10)));
+
+        // wraps to the right edge of the next row
+        let wrapped = placer.next(Size::new(60, 10)).unwrap();
+        assert_eq!(wrapped.top_left, Point::new(40, 14));
+        assert_eq!(
+            placer.free_rect(),
+            Rectangle::new(Point::new(0, 14), Size::new(40, 36))
+        );
+    }
+
+    #[test]
+    fn test_placer_right_to_left_aligned_to_end() {
+        let mut placer = placer(Align(HorizontalAlign::Right, VerticalAlign::Top));
+        placer.set_direction(LayoutDirection::RightToLeft);
+        // the end of the row is at the left edge
+        assert_eq!(
+            placer.next(Size::new(20, 10)).unwrap().top_left,
+            Point::new(0, 0)
+        );
+    }
+
+    #[test]
+    fn test_placer_right_to_left_shrink() {
+        let mut placer = placer(Align::default());
+        placer.set_direction(LayoutDirection::RightToLeft);
+        placer.next(Size::new(20, 10)).unwrap();
+
+        // shrinking at the left edge doesn't move the position, which is at the right
+        placer.shrink(Size::new(10, 0), Size::zero());
+        assert_eq!(
+            placer.next(Size::new(20, 10)).unwrap().top_left,
+            Point::new(50, 0)
+        );
+    }
+
+    #[test]
+    fn test_right_to_left_layout() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        // 58x58 usable area at (3, 3)
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_layout_direction(LayoutDirection::RightToLeft);
+
+        let first = ui
+            .add_horizontal(Spacer::new(Size::new(10, 5)))
+            .internal
+            .area;
+        let second = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+        assert_eq!(first.top_left, Point::new(51, 3));
+        assert_eq!(second.top_left, Point::new(38, 3));
+
+        // sub-uis inherit the direction
+        let mut nested = Rectangle::zero();
+        ui.vertical(|ui| {
+            nested = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(nested.top_left, Point::new(56, 12));
+
+        // the minimum of a slider is on the right, so clicking on the left selects the maximum
+        ui.new_row();
+        let mut value = 0;
+        let area = ui
+            .add(Slider::new(&mut value, 0..=100).width(30))
+            .internal
+            .area;
+        assert_eq!(area.top_left.x + area.size.width as i32, 61);
+
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_layout_direction(LayoutDirection::RightToLeft);
+        ui.interact(Interaction::Click(Point::new(area.top_left.x + 1, 10)));
+        ui.add(Slider::new(&mut value, 0..=100).width(30));
+        assert_eq!(value, 100);
+    }
+
+    #[test]
+    fn test_rotation_mapping() {
+        let physical = Rectangle::new(Point::new(10, 20), Size::new(40, 30));
+        for rotation in [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ] {
+            let logical = rotation.logical_bounds(physical);
+            for point in [logical.top_left, logical.bottom_right().unwrap()] {
+                let mapped = rotation.to_physical(point, physical);
+                assert!(physical.contains(mapped), "{:?}", rotation);
+                assert_eq!(rotation.to_logical(mapped, physical), point);
+            }
+        }
+
+        // the top left corner of the UI ends up in the top right corner of the display
+        assert_eq!(
+            Rotation::Deg90.to_physical(Point::new(10, 20), physical),
+            Point::new(49, 20)
+        );
+        assert_eq!(
+            Rotation::Deg90.rect_to_physical(
+                Rectangle::new(Point::new(10, 20), Size::new(5, 2)),
+                physical
+            ),
+            Rectangle::new(Point::new(48, 20), Size::new(2, 5))
+        );
+    }
+
+    #[test]
+    fn test_rotated_ui() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui =
+            Ui::new_fullscreen_rotated(&mut display, medsize_rgb565_style(), Rotation::Deg90);
+        assert_eq!(ui.rotation(), Rotation::Deg90);
+
+        // the top of the UI is at the right edge of the display, so this is close to its top left corner
+        ui.interact(Interaction::Release(Point::new(58, 5)));
+        let clicked = ui.add(Button::new("A")).clicked();
+        let separator = ui.add(Separator::horizontal().margin(2)).internal.area;
+        assert!(clicked);
+
+        // the horizontal separator line is drawn as a vertical line
+        let line_y = separator.top_left.y + 2;
+        assert_eq!(
+            display.get_pixel(Point::new(63 - line_y, 30)),
+            Some(Rgb565::WHITE)
+        );
+        assert_ne!(
+            display.get_pixel(Point::new(30, line_y)),
+            Some(Rgb565::WHITE)
+        );
+    }
+
+    #[test]
+    fn test_overlay_on_top_of_later_widgets() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::Release(Point::new(10, 45)));
+
+        // queued from within a sub-ui, at the bottom of the screen
+        let area = Rectangle::new(Point::new(3, 40), Size::new(40, 20));
+        ui.vertical(|ui| ui.overlay(area, 7)).unwrap();
+
+        // widgets added afterwards neither draw nor get interactions inside of the overlay
+        ui.draw(
+            &Rectangle::new(Point::zero(), Size::new(64, 64))
+                .into_styled(PrimitiveStyle::with_fill(Rgb565::WHITE)),
+        )
+        .unwrap();
+        assert_eq!(ui.check_interact(area), Interaction::None);
+
+        // the content is added at the end of the frame
+        let mut ids = Vec::<usize, 2>::new();
+        let mut clicked_inside = false;
+        let overlays = ui
+            .finish(|ui, id| {
+                ids.push(id).unwrap();
+                clicked_inside = ui.add(Spacer::new(Size::new(40, 20))).internal.interaction
+                    != Interaction::None;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(ids.as_slice(), &[7]);
+        assert!(clicked_inside);
+        assert_eq!(overlays.areas(), &[area]);
+
+        assert_eq!(display.get_pixel(Point::new(1, 45)), Some(Rgb565::WHITE));
+        assert_ne!(display.get_pixel(Point::new(10, 45)), Some(Rgb565::WHITE));
+    }
+
+    #[test]
+    fn test_overlays_drawn_in_order() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        ui.overlay(Rectangle::new(Point::new(0, 0), Size::new(20, 20)), 0)
+            .unwrap();
+        ui.overlay(Rectangle::new(Point::new(10, 10), Size::new(20, 20)), 1)
+            .unwrap();
+
+        let mut ids = Vec::<usize, 4>::new();
+        let overlays = ui
+            .finish(|ui, id| {
+                ids.push(id).unwrap();
+                let color = if id == 0 { Rgb565::RED } else { Rgb565::GREEN };
+                ui.draw(&ui.bounds().into_styled(PrimitiveStyle::with_fill(color)))?;
+                // overlays can queue further overlays
+                if id == 1 {
+                    ui.overlay(Rectangle::new(Point::new(40, 40), Size::new(5, 5)), 2)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(ids.as_slice(), &[0, 1, 2]);
+        assert_eq!(overlays.areas().len(), 3);
+        // the later overlay is on top of the earlier one
+        assert_eq!(display.get_pixel(Point::new(5, 5)), Some(Rgb565::RED));
+        assert_eq!(display.get_pixel(Point::new(15, 15)), Some(Rgb565::GREEN));
+    }
+
+    #[test]
+    fn test_previous_overlays_take_precedence() {
+        let area = Rectangle::new(Point::new(3, 3), Size::new(40, 20));
+        let mut overlays = OverlayAreas::new();
+
+        for frame in 0..2 {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.set_previous_overlays(&overlays);
+            ui.interact(Interaction::Release(Point::new(10, 10)));
+
+            // the button below the overlay only gets the click before the overlay was shown
+            let clicked = ui.add(Button::new("A")).clicked();
+            assert_eq!(clicked, frame == 0);
+
+            ui.overlay(area, 0).unwrap();
+            let mut overlay_clicked = false;
+            overlays = ui
+                .finish(|ui, _| {
+                    overlay_clicked = ui.check_interact(area) != Interaction::None;
+                    Ok(())
+                })
+                .unwrap();
+            assert!(overlay_clicked);
+        }
+    }
+
+    #[test]
+    fn test_removed_overlay_doesnt_block() {
+        let area = Rectangle::new(Point::new(3, 3), Size::new(40, 20));
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.overlay(area, 0).unwrap();
+        let mut overlays = ui.finish(|_, _| Ok(())).unwrap();
+
+        // the popup was closed by the last frame's interaction
+        overlays.remove(area);
+        assert!(!overlays.contains(Point::new(10, 10)));
+
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.set_previous_overlays(&overlays);
+        ui.interact(Interaction::Release(Point::new(10, 10)));
+        assert!(ui.add(Button::new("A")).clicked());
+    }
+
+    #[test]
+    fn test_horizontal_combines_responses() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let mut areas = [Rectangle::zero(); 2];
+        let response = ui.horizontal(|ui| {
+            areas[0] = ui.add(Spacer::new(Size::new(10, 5))).internal.area;
+            areas[1] = ui.add(Spacer::new(Size::new(20, 12))).internal.area;
+            Ok(())
+        });
+
+        // both widgets are in one row
+        assert_eq!(areas[0].top_left, Point::new(3, 3));
+        assert_eq!(areas[1].top_left, Point::new(21, 3));
+        assert_eq!(
+            response.internal.area,
+            Rectangle::new(Point::new(3, 3), Size::new(38, 12))
+        );
+        assert!(!response.clicked());
+
+        // a new row is started afterwards
+        let next = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+        assert_eq!(next.top_left, Point::new(3, 19));
+    }
+
+    #[test]
+    fn test_horizontal_aligned_as_a_whole() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        // 38px (including the spacing) in the 58px usable width
+        for (align, x) in [
+            (HorizontalAlign::Left, 3),
+            (HorizontalAlign::Center, 13),
+            (HorizontalAlign::Right, 23),
+        ] {
+            ui.set_align(Align(align, VerticalAlign::Top));
+            let mut areas = [Rectangle::zero(); 2];
+            let response = ui.horizontal(|ui| {
+                areas[0] = ui.add(Spacer::new(Size::new(10, 5))).internal.area;
+                areas[1] = ui.add(Spacer::new(Size::new(20, 5))).internal.area;
+                Ok(())
+            });
+
+            // the widgets keep their order
+            assert_eq!(areas[0].top_left.x, x);
+            assert_eq!(areas[1].top_left.x, x + 18);
+            assert_eq!(response.internal.area.size.width, 38);
+            // the alignment is kept for the following rows
+            assert_eq!(ui.align().0 as u8, align as u8);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_measure_pass_keeps_smartstates() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut smartstates = SmartstateProvider::<4>::new();
+
+        for first_frame in [true, false] {
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.set_align(Align(HorizontalAlign::Center, VerticalAlign::Top));
+            smartstates.restart_counter();
+
+            // whether the labels' smartstates were still unset, for each call
+            let mut unset = [false; 2];
+            let mut calls = 0;
+            ui.horizontal_with_smartstates(&mut smartstates, |ui, smartstates| {
+                unset[calls] = smartstates.peek().is_empty();
+                calls += 1;
+                ui.add(Label::new("A").smartstate(smartstates.nxt()));
+                ui.add(Label::new("B").smartstate(smartstates.nxt()));
+                Ok(())
+            });
+
+            // measured (without touching the smartstates), then drawn with the same smartstates
+            assert_eq!(calls, 2);
+            assert_eq!(smartstates.get_pos(), 2);
+            assert_eq!(unset, [first_frame; 2]);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_clicked_if_any_child_is() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::Release(Point::new(40, 10)));
+
+        let mut inner = false;
+        let response = ui.horizontal(|ui| {
+            ui.add(Label::new("A"));
+            // nested groups are combined, too
+            inner = ui
+                .horizontal(|ui| {
+                    ui.add(Button::new("B"));
+                    Ok(())
+                })
+                .clicked();
+            Ok(())
+        });
+        assert!(inner);
+        assert!(response.clicked());
+        assert!(response.redrawn());
+
+        // widgets that don't fit into the row are reported as an error
+        let response = ui.horizontal(|ui| {
+            ui.add(Spacer::new(Size::new(40, 5)));
+            ui.add(Spacer::new(Size::new(40, 5)));
+            Ok(())
+        });
+        assert_eq!(response.error(), Some(GuiError::NoSpaceLeft));
+    }
+
+    #[test]
+    fn test_focus_navigation() {
+        use crate::checkbox::Checkbox;
+
+        let mut checked = false;
+        let mut frame = |focus: FocusState, interaction: Interaction| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.set_focus_state(focus);
+            ui.interact(interaction);
+            let activated = [
+                ui.add_horizontal(Button::new("A")).clicked(),
+                ui.add_horizontal(Label::new("not focusable")).clicked(),
+                ui.add_horizontal(Checkbox::new(&mut checked)).changed(),
+            ];
+            (ui.focus_state(), activated)
+        };
+
+        // the first frame has no focusable widgets yet, so the focus moves at its end
+        let (focus, _) = frame(FocusState::new(), Interaction::FocusNext);
+        assert_eq!(focus.focused(), Some(0));
+        assert_eq!(focus.count(), 2);
+        let (focus, _) = frame(focus, Interaction::FocusNext);
+        assert_eq!(focus.focused(), Some(1));
+        // wraps around in both directions
+        let (focus, _) = frame(focus, Interaction::FocusNext);
+        assert_eq!(focus.focused(), Some(0));
+        let (focus, _) = frame(focus, Interaction::FocusPrev);
+        assert_eq!(focus.focused(), Some(1));
+
+        // activating clicks the focused widget only
+        let (focus, activated) = frame(focus, Interaction::Activate);
+        assert_eq!(activated, [false, false, true]);
+        assert_eq!(focus.focused(), Some(1));
+        assert!(checked);
+    }
+
+    #[test]
+    fn test_focus_call_order() {
+        let frame = |focus: FocusState, interaction, interact_first: bool, nested: bool| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            if interact_first {
+                ui.interact(interaction);
+                ui.set_focus_state(focus);
+            } else {
+                ui.set_focus_state(focus);
+                ui.interact(interaction);
+            }
+            let buttons = |ui: &mut Ui<_, _>| {
+                ui.add(Button::new("A"));
+                ui.add(Button::new("B"));
+                ui.add(Button::new("C"));
+                Ok(())
+            };
+            if nested {
+                ui.horizontal(buttons);
+            } else {
+                buttons(&mut ui).unwrap();
+            }
+            ui.focus_state()
+        };
+
+        let mut focus = FocusState::new();
+        focus.set_focused(Some(0));
+        let focus = frame(focus, Interaction::None, false, false);
+        for interact_first in [false, true] {
+            for nested in [false, true] {
+                // the focus moves once, no matter the call order or where the widgets are
+                assert_eq!(
+                    frame(focus, Interaction::FocusNext, interact_first, nested).focused(),
+                    Some(1),
+                    "interact first: {interact_first}, nested: {nested}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_slider_release_isnt_a_click() {
+        use crate::slider::Slider;
+
+        let mut value = 0;
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::Release(Point::new(10, 10)));
+        let response = ui.add(Slider::new(&mut value, 0..=100).width(50));
+        assert!(!response.clicked());
+    }
+
+    #[test]
+    fn test_focus_navigate_directions() {
+        // 2x2 grid of buttons: 0 1 / 2 3
+        let frame = |focus: FocusState, interaction: Interaction| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.set_focus_state(focus);
+            ui.interact(interaction);
+            let back = [
+                ui.add_horizontal(Button::new("A")).back(),
+                ui.add(Button::new("B")).back(),
+                ui.add_horizontal(Button::new("C")).back(),
+                ui.add(Button::new("D")).back(),
+            ];
+            (ui.focus_state(), back)
+        };
+        let navigate = |focus, direction| frame(focus, Interaction::Navigate(direction)).0;
+
+        // without a focused widget, the first one is focused
+        let focus = navigate(FocusState::new(), Direction::Down);
+        assert_eq!(focus.focused(), Some(0));
+        // the area of the focused widget is recorded in the next frame
+        let (focus, _) = frame(focus, Interaction::None);
+
+        let right = navigate(focus, Direction::Right);
+        assert_eq!(right.focused(), Some(1));
+        assert_eq!(navigate(focus, Direction::Down).focused(), Some(2));
+        assert_eq!(navigate(right, Direction::Down).focused(), Some(3));
+        assert_eq!(navigate(right, Direction::Left).focused(), Some(0));
+
+        // wraps around at the screen edges, staying in the row / column
+        assert_eq!(navigate(right, Direction::Right).focused(), Some(0));
+        assert_eq!(navigate(focus, Direction::Up).focused(), Some(2));
+
+        // the back input is reported by the focused widget
+        let (focus, back) = frame(right, Interaction::Back);
+        assert_eq!(back, [false, true, false, false]);
+        assert_eq!(focus.focused(), Some(1));
+    }
+
+    #[test]
+    fn test_rotary_encoder() {
+        use crate::slider::Slider;
+
+        fn frame(
+            display: &mut MockDisplay<Rgb565>,
+            value: &mut i16,
+            focus: FocusState,
+            interaction: Interaction,
+        ) -> (FocusState, bool, Rectangle) {
+            let mut ui = Ui::new_fullscreen(display, medsize_rgb565_style());
+            ui.set_focus_state(focus);
+            ui.interact(interaction);
+            let clicked = ui.add_horizontal(Button::new("A")).clicked();
+            let area = ui
+                .add(Slider::new(value, 0..=100).step_size(5).width(10))
+                .internal
+                .area;
+            (ui.focus_state(), clicked, area)
+        }
+
+        let style = medsize_rgb565_style();
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut value = 0;
+        let mut scroll =
+            |focus, delta| frame(&mut display, &mut value, focus, Interaction::Scroll(delta)).0;
+
+        // scrolling cycles the focus
+        let focus = scroll(FocusState::new(), 1);
+        assert_eq!(focus.focused(), Some(0));
+        let focus = scroll(focus, 1);
+        assert_eq!(focus.focused(), Some(1));
+        let focus = scroll(focus, 1);
+        assert_eq!(focus.focused(), Some(0));
+        let focus = scroll(focus, -1);
+        assert_eq!(focus.focused(), Some(1));
+
+        // pressing the encoder on the slider enters the edit mode
+        let (focus, clicked, area) = frame(&mut display, &mut value, focus, Interaction::Activate);
+        assert!(focus.editing());
+        assert!(!clicked);
+        let corner = area.top_left - Point::new(1, 1);
+        assert_eq!(display.get_pixel(corner), Some(style.secondary_color));
+
+        // in edit mode, scrolling changes the value by the step size
+        let (focus, ..) = frame(&mut display, &mut value, focus, Interaction::Scroll(2));
+        assert_eq!(focus.focused(), Some(1));
+        assert_eq!(value, 10);
+        let (focus, ..) = frame(&mut display, &mut value, focus, Interaction::Scroll(-5));
+        assert_eq!(value, 0);
+        // large deltas saturate at the ends of the range
+        let (focus, ..) = frame(
+            &mut display,
+            &mut value,
+            focus,
+            Interaction::Scroll(i32::MAX / 2),
+        );
+        assert_eq!(value, 100);
+        let (focus, ..) = frame(
+            &mut display,
+            &mut value,
+            focus,
+            Interaction::Scroll(i32::MIN),
+        );
+        assert_eq!(value, 0);
+
+        // devices with buttons only change the value by one step
+        let (focus, ..) = frame(&mut display, &mut value, focus, Interaction::FocusNext);
+        assert_eq!(value, 5);
+        let (focus, ..) = frame(
+            &mut display,
+            &mut value,
+            focus,
+            Interaction::Navigate(Direction::Right),
+        );
+        assert_eq!(value, 10);
+        let (focus, ..) = frame(&mut display, &mut value, focus, Interaction::FocusPrev);
+        assert_eq!(value, 5);
+        let (focus, ..) = frame(
+            &mut display,
+            &mut value,
+            focus,
+            Interaction::Navigate(Direction::Left),
+        );
+        assert_eq!(value, 0);
+        assert!(focus.editing());
+        assert_eq!(focus.focused(), Some(1));
+
+        // pressing it again leaves the edit mode
+        let (focus, ..) = frame(&mut display, &mut value, focus, Interaction::Activate);
+        assert!(!focus.editing());
+        assert_eq!(
+            display.get_pixel(corner),
+            Some(style.highlight_border_color)
+        );
+        let (focus, ..) = frame(&mut display, &mut value, focus, Interaction::Scroll(1));
+        assert_eq!(focus.focused(), Some(0));
+        assert_eq!(value, 0);
+    }
+
+    #[test]
+    fn test_focus_ring() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let style = medsize_rgb565_style();
+
+        let mut focus = FocusState::new();
+        focus.set_focused(Some(0));
+        let mut ui = Ui::new_fullscreen(&mut display, style);
+        ui.set_focus_state(focus);
+        let area = ui.add(Button::new("A")).internal.area;
+        ui.add(Button::new("B"));
+        let focus = ui.focus_state();
+
+        // the ring is drawn just outside of the focused widget
+        let corner = area.top_left - Point::new(1, 1);
+        assert_eq!(
+            display.get_pixel(corner),
+            Some(style.highlight_border_color)
+        );
+
+        // the ring is removed once the widget isn't focused anymore
+        let mut ui = Ui::new_fullscreen(&mut display, style);
+        ui.set_focus_state(focus);
+        ui.interact(Interaction::FocusNext);
+        ui.add(Button::new("A"));
+        ui.add(Button::new("B"));
+        assert_eq!(ui.focus_state().focused(), Some(1));
+        assert_eq!(display.get_pixel(corner), Some(style.background_color));
+    }
+
+    #[test]
+    fn test_painter_clip() {
+        use crate::style::medsize_rgb565_style;
+        use embedded_graphics::mock_display::MockDisplay;
+        use embedded_graphics::pixelcolor::Rgb565;
+
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        let clip = Rectangle::new(Point::new(2, 2), Size::new(2, 2));
+        ui.interact(Interaction::Click(Point::new(1, 1)));
+
+        ui.clipped_area_ui(Rectangle::new(Point::zero(), Size::new(8, 8)), clip, |ui| {
+            let area = Rectangle::new(Point::zero(), Size::new(8, 8));
+            // the click is outside of the visible area
+            assert_eq!(ui.check_interact(area), Interaction::None);
+            ui.draw(&area.into_styled(PrimitiveStyle::with_fill(Rgb565::RED)))
+        })
+        .0
+        .unwrap();
+
+        display.assert_pattern(&["    ", "    ", "  RR", "  RR"]);
+    }
+
+    fn assert_measured(widget: impl Widget) {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let measured = ui.measure(&widget);
+        let area = ui.add(widget).internal.area;
+        assert_eq!(measured, Some(area.size));
+    }
+
+    #[test]
+    fn test_measure_matches_allocation() {
+        let (mut checked, mut active, mut value) = (false, false, 0);
+
+        assert_measured(Label::new("Hi"));
+        assert_measured(Button::new("Hi"));
+        assert_measured(IconButton::new(size12px::actions::Check));
+        assert_measured(IconButton::new(size12px::actions::Check).label("Ok"));
+        assert_measured(Checkbox::new(&mut checked));
+        assert_measured(ToggleSwitch::new(&mut active).width(30).height(15));
+        assert_measured(ToggleButton::new("Hi", &mut active));
+        assert_measured(Slider::new(&mut value, 0..=10).width(40).label("X"));
+        assert_measured(IconWidget::new(size12px::actions::Check));
+        assert_measured(Spacer::new(Size::new(5, 7)));
+        assert_measured(Separator::horizontal());
+    }
+
+    #[test]
+    fn test_docked_panels() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        // 58x58 usable area at (3, 3)
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let mut header = Rectangle::zero();
+        ui.top_panel_ui(10, false, |ui| {
+            header = ui.add(Spacer::new(Size::new(1, 1))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+        let mut rail = Rectangle::zero();
+        ui.left_panel_ui(12, false, |ui| {
+            rail = ui.add(Spacer::new(Size::new(1, 1))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+        ui.bottom_panel_ui(10, false, |_| Ok(())).unwrap();
+        assert_eq!(
+            ui.right_panel_ui(100, false, |_| Ok(())),
+            Err(GuiError::BoundsError)
+        );
+
+        // panel content is inset by the border padding
+        assert_eq!(header.top_left, Point::new(6, 6));
+        assert_eq!(rail.top_left, Point::new(6, 16));
+
+        // the remaining area is next to the panels
+        assert_eq!(ui.space_available(), Size::new(46, 38));
+        let area = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+        assert_eq!(area.top_left, Point::new(15, 13));
+    }
+
+    #[test]
+    fn test_panels_below_current_row() {
+        for side in [PanelSide::Left, PanelSide::Top] {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+            let spacer = ui
+                .add_horizontal(Spacer::new(Size::new(10, 10)))
+                .internal
+                .area;
+            assert_eq!(spacer.top_left, Point::new(3, 3));
+
+            let mut content = Rectangle::zero();
+            ui.panel_ui(side, 15, false, false, |ui| {
+                content = ui.add(Spacer::new(Size::new(1, 1))).internal.area;
+                Ok(())
+            })
+            .unwrap();
+
+            // the panel starts below the row of the spacer (and its spacing)
+            assert!(spacer.intersection(&content).is_zero_sized());
+            assert_eq!(content.top_left, Point::new(6, 20));
+        }
+    }
+
+    #[test]
+    fn test_flush_panels() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let mut header = Rectangle::zero();
+        ui.panel_ui(PanelSide::Top, 10, false, true, |ui| {
+            header = ui.add(Spacer::new(Size::new(1, 1))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+        let mut footer = Rectangle::zero();
+        ui.panel_ui(PanelSide::Bottom, 10, false, true, |ui| {
+            footer = ui.add(Spacer::new(Size::new(64, 10))).internal.area;
+            Ok(())
+        })
+        .unwrap();
+
+        // the panels reach the edges of the screen
+        assert_eq!(header.top_left, Point::new(0, 0));
+        assert_eq!(footer, Rectangle::new(Point::new(0, 54), Size::new(64, 10)));
+
+        // the remaining area keeps its padding towards the panels
+        assert_eq!(ui.space_available(), Size::new(58, 38));
+        let area = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+        assert_eq!(area.top_left, Point::new(3, 13));
+    }
+
+    #[test]
+    fn test_put() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(Interaction::Release(Point::new(42, 32)));
+
+        let area = Rectangle::new(Point::new(40, 30), Size::new(10, 10));
+        let res = ui.put(area, Spacer::new(Size::new(5, 5)));
+        assert_eq!(
+            res.internal.area,
+            Rectangle::new(Point::new(40, 30), Size::new(5, 5))
+        );
+        assert!(matches!(res.internal.interaction, Interaction::Release(_)));
+
+        // too large for the rectangle
+        assert!(ui.put(area, Spacer::new(Size::new(11, 5))).error.is_some());
+
+        // the placer position is untouched
+        let next = ui.add(Spacer::new(Size::new(5, 5))).internal.area;
+        assert_eq!(next.top_left, Point::new(3, 3));
+    }
+
+    #[test]
+    fn test_label_shrink_to_fit() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        let text = "This text is far too long for the display";
+
+        assert_eq!(
+            ui.add(Label::new(text)).error,
+            Some(GuiError::WidgetTooLarge)
+        );
+
+        ui.set_shrink_to_fit(true);
+        let res = ui.add(Label::new(text));
+        assert!(res.error.is_none());
+        assert_eq!(res.internal.area.size.width, 58);
+    }
+
+    #[test]
+    fn test_label_shrink_to_fit_barely_too_wide() {
+        // texts only a few pixels wider than the 58px usable width
+        for text in ["abcdefg", "abcdefgh", "abcdefghi"] {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.set_shrink_to_fit(true);
+            let res = ui.add(Label::new(text));
+            assert!(res.error.is_none());
+            assert!(res.internal.area.size.width <= 58);
+
+            let hasher = Hasher::new();
+            let mut smartstate = Smartstate::empty();
+            let res = ui.add(HashLabel::new(text, &mut smartstate, &hasher));
+            assert!(res.error.is_none());
+            assert!(res.internal.area.size.width <= 58);
+        }
+    }
+
+    #[test]
+    fn test_modal_captures_input() {
+        let spacer = || Spacer::new(Size::new(5, 5));
+        for (point, inside) in [(Point::new(16, 16), true), (Point::new(4, 4), false)] {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            ui.interact(Interaction::Release(point));
+
+            // 40x40 dialog centered in the 58x58 usable area, with 3px padding
+            let mut dialog = Response::new(InternalResponse::empty());
+            ui.modal(true, 40, 40, &mut Smartstate::empty(), |ui| {
+                dialog = ui.add(spacer());
+                Ok(())
+            })
+            .unwrap();
+            assert_eq!(dialog.internal.area.top_left, Point::new(15, 15));
+            assert_eq!(
+                matches!(dialog.internal.interaction, Interaction::Release(_)),
+                inside
+            );
+
+            // the widgets behind the dialog don't get any interaction
+            let behind = ui.add(spacer());
+            assert_eq!(behind.internal.area.top_left, Point::new(3, 3));
+            assert_eq!(behind.internal.interaction, Interaction::None);
+        }
+    }
+
+    #[test]
+    fn test_modal_too_large() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        assert_eq!(
+            ui.modal(true, 59, 20, &mut Smartstate::empty(), |_| Ok(())),
+            Err(GuiError::BoundsError)
+        );
+    }
+
+    #[test]
+    fn test_modal_frame_drawn_on_open_and_cleared_on_close() {
+        let style = medsize_rgb565_style();
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut smartstate = Smartstate::empty();
+        // the top left corner of the 40x40 dialog's frame, centered in the 58x58 usable area
+        let corner = Point::new(12, 12);
+
+        let mut frame = |display: &mut MockDisplay<Rgb565>, open: bool| {
+            let mut ui = Ui::new_fullscreen(display, style);
+            ui.modal(open, 40, 40, &mut smartstate, |_| Ok(())).unwrap();
+        };
+
+        frame(&mut display, true);
+        assert_eq!(
+            display.get_pixel(corner),
+            Some(style.highlight_border_color)
+        );
+
+        // while the dialog stays open, nothing is drawn again (so it doesn't flicker)
+        display.set_pixel(corner, None);
+        frame(&mut display, true);
+        assert_eq!(display.get_pixel(corner), None);
+
+        // closing it clears the dialog
+        frame(&mut display, false);
+        assert_eq!(display.get_pixel(corner), Some(style.background_color));
+        display.set_pixel(corner, None);
+        frame(&mut display, false);
+        assert_eq!(display.get_pixel(corner), None);
+    }
+
+    #[test]
+    fn test_modal_restores_background() {
+        let mut smartstates = SmartstateProvider::<4>::new();
+
+        // draws a frame, returning whether the widget behind the dialog had to be redrawn
+        let mut frame = |open: bool| {
+            let mut display = MockDisplay::<Rgb565>::new();
+            display.set_allow_overdraw(true);
+            let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+            smartstates.restart_counter();
+
+            ui.modal_with_smartstates(open, 40, 40, &mut smartstates, |ui, smartstates| {
+                ui.add(Label::new("?").smartstate(smartstates.nxt()));
+                Ok(())
+            })
+            .unwrap();
+            let redraw = *smartstates.peek() != Smartstate::state(0);
+            ui.add(Label::new("A").smartstate(smartstates.nxt()));
+            redraw
+        };
+
+        assert!(frame(false));
+        assert!(!frame(false));
+        // opening and closing the dialog both force the background to redraw
+        assert!(frame(true));
+        assert!(!frame(true));
+        assert!(frame(false));
+        assert!(!frame(false));
+    }
+
+    #[test]
+    fn test_add_with_margins() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let margins = Margins {
+            left: 4,
+            top: 2,
+            right: 6,
+            bottom: 3,
+        };
+        let first = ui.add_horizontal_with(Spacer::new(Size::new(10, 5)), margins);
+        assert_eq!(
+            first.internal.area,
+            Rectangle::new(Point::new(7, 5), Size::new(10, 5))
+        );
+
+        // the right margin and the item spacing come after the widget
+        let second = ui.add(Spacer::new(Size::new(10, 5))).internal.area;
+        assert_eq!(second.top_left, Point::new(3 + 20 + 8, 3));
+
+        // the row is as high as the widget with its margins
+        let third = ui.add(Spacer::new(Size::new(10, 5))).internal.area;
+        assert_eq!(third.top_left, Point::new(3, 3 + 10 + 4));
+    }
+
+    #[test]
+    fn test_add_with_padding() {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        let default = ui.add(Button::new("A")).internal.area.size;
+        let padded = ui
+            .add_with(
+                Button::new("A"),
+                WidgetSpacing::new().padding(Size::new(10, 6)),
+            )
+            .internal
+            .area
+            .size;
+        // 6x5 padding by default
+        assert_eq!(padded, default + Size::new(8, 2));
+
+        // the style is restored afterwards
+        assert_eq!(ui.style().spacing.button_padding, Size::new(6, 5));
+    }
+}
@@ -0,0 +1,871 @@
+//! # Window Manager
+//!
+//! The [WindowManager] shows several floating windows on top of a main view, e.g. a status popup
+//! over the main view, and a numeric entry over that.
+//!
+//! ## Core Features
+//!
+//! - Windows with a title bar, a position and a z-order
+//! - Input goes to the topmost window under the pointer (or the main view, if there's no window)
+//! - Clicking a window raises it to the top, dragging its title bar moves it
+//! - Windows don't draw over the windows above them, so they can be drawn in any order
+//! - Lower windows (and the main view) are redrawn where an upper window moved away or was closed
+//!
+//! ## Usage
+//!
+//! The [WindowManager] keeps the position and z-order of the windows, so it has to live across frames.
+//! Windows are identified by their index (`0..N`).
+//!
+//! ```no_run
+//! # use embedded_graphics::pixelcolor::Rgb565;
+//! # use embedded_graphics_simulator::{SimulatorDisplay, OutputSettingsBuilder, Window};
+//! # use kolibri_embedded_gui::style::medsize_rgb565_style;
+//! # use kolibri_embedded_gui::ui::Ui;
+//! # use embedded_graphics::prelude::*;
+//! # use embedded_graphics::primitives::Rectangle;
+//! # use kolibri_embedded_gui::label::Label;
+//! # use kolibri_embedded_gui::button::Button;
+//! # use kolibri_embedded_gui::window::WindowManager;
+//! # let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(320, 240));
+//! # let output_settings = OutputSettingsBuilder::new().build();
+//! # let mut window = Window::new("Kolibri Example", &output_settings);
+//! const STATUS: usize = 0;
+//! const ENTRY: usize = 1;
+//!
+//! let mut windows = WindowManager::<2>::new();
+//! windows.open(STATUS, Rectangle::new(Point::new(20, 20), Size::new(160, 100)));
+//! windows.open(ENTRY, Rectangle::new(Point::new(80, 60), Size::new(160, 120)));
+//!
+//! loop {
+//!     let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+//!     // ui.interact(...);
+//!
+//!     windows.background(&mut ui, |ui, _| {
+//!         ui.add(Label::new("Main view"));
+//!         Ok(())
+//!     }).unwrap();
+//!     windows.window(&mut ui, STATUS, "Status", |ui, _| {
+//!         ui.add(Label::new("All good"));
+//!         Ok(())
+//!     }).unwrap();
+//!     let mut done = false;
+//!     windows.window(&mut ui, ENTRY, "Entry", |ui, _| {
+//!         done = ui.add(Button::new("Done")).clicked();
+//!         Ok(())
+//!     }).unwrap();
+//!     if done {
+//!         windows.close(ENTRY);
+//!     }
+//!     # break;
+//! }
+//! ```
+//!
+//! ## Implementation Details
+//!
+//! Each window is drawn in its own [Ui] (see [Ui::unchecked_sub_ui]), whose painter excludes the areas of all
+//! windows above it. Because of that, a window never draws over an upper window, no matter in which order the
+//! windows are drawn. The painter can exclude at most 8 areas, so at most 8 windows can be on top of a window.
+//!
+//! A window is *invalidated* (its area cleared and its frame and content redrawn) when it's opened, raised, or moved,
+//! or when a window above it moved away from or closed over it. The main view is invalidated the same way, but only
+//! the parts that windows moved away from are cleared (the rest stays on the screen, so dragging a window doesn't
+//! clear the whole screen in every frame).
+//! Content drawn with smartstates needs to force them to redraw in that case: the content closures get a flag for this,
+//! and [WindowManager::window_with_smartstates] and [WindowManager::background_with_smartstates] do it automatically.
+//! Changes to the windows made between drawing windows (e.g. closing a window with a button in it) take effect for
+//! the windows drawn afterwards, so the remaining invalidations are done in the next frame.
+
+use crate::smartstate::SmartstateProvider;
+use crate::ui::{GuiError, GuiResult, Interaction, InternalResponse, Response, Ui};
+use core::cmp::max;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::PixelColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyleBuilder, Rectangle};
+use embedded_graphics::text::{Baseline, Text};
+use heapless::Vec;
+
+/// State of a single window, kept across frames
+#[derive(Clone, Copy, Debug)]
+struct WindowState {
+    area: Rectangle,
+    /// Windows with a higher z are on top
+    z: u32,
+    open: bool,
+    /// Whether the window has to be redrawn completely
+    invalid: bool,
+}
+
+/// Manages overlapping windows on top of a main view. See the [module docs](crate::window) for details.
+#[derive(Clone, Debug)]
+pub struct WindowManager<const N: usize> {
+    windows: [WindowState; N],
+    /// The highest z of all windows
+    top_z: u32,
+    /// Window that's being dragged by its title bar, and where it was grabbed (relative to its top left corner)
+    drag: Option<(usize, Point)>,
+    /// Whether the main view has to be redrawn completely
+    background_invalid: bool,
+    /// Areas of the main view that windows moved away from, which have to be cleared and redrawn
+    background_damage: Vec<Rectangle, MAX_DAMAGE>,
+}
+
+impl<const N: usize> Default for WindowManager<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> WindowManager<N> {
+    /// Creates a new window manager with `N` closed windows.
+    pub fn new() -> Self {
+        WindowManager {
+            windows: [WindowState {
+                area: Rectangle::zero(),
+                z: 0,
+                open: false,
+                invalid: true,
+            }; N],
+            top_z: 0,
+            drag: None,
+            background_invalid: true,
+            background_damage: Vec::new(),
+        }
+    }
+
+    /// Opens the window `id` with the given (absolute) area, on top of all other windows.
+    ///
+    /// If the window is already open, it's moved to the area and raised.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `id` is not less than `N`.
+    pub fn open(&mut self, id: usize, area: Rectangle) {
+        if self.windows[id].open {
+            self.invalidate_below(id, Some(area));
+        }
+        let window = &mut self.windows[id];
+        window.area = area;
+        window.open = true;
+        window.invalid = true;
+        self.raise(id);
+    }
+
+    /// Closes the window `id`, redrawing the windows (and the main view) below it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `id` is not less than `N`.
+    pub fn close(&mut self, id: usize) {
+        if self.windows[id].open {
+            self.invalidate_below(id, None);
+            self.windows[id].open = false;
+        }
+        if self.drag.is_some_and(|(dragged, _)| dragged == id) {
+            self.drag = None;
+        }
+    }
+
+    /// Returns whether the window `id` is open.
+    pub fn is_open(&self, id: usize) -> bool {
+        self.windows.get(id).is_some_and(|window| window.open)
+    }
+
+    /// Returns the (absolute) area of the window `id`, if it's open.
+    pub fn area(&self, id: usize) -> Option<Rectangle> {
+        self.windows
+            .get(id)
+            .filter(|window| window.open)
+            .map(|window| window.area)
+    }
+
+    /// Moves the window `id` to the top of all other windows.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `id` is not less than `N`.
+    pub fn raise(&mut self, id: usize) {
+        if self.windows[id].z == self.top_z && self.top_z != 0 {
+            return;
+        }
+        self.top_z += 1;
+        self.windows[id].z = self.top_z;
+        self.windows[id].invalid = true;
+    }
+
+    /// Moves the top left corner of the window `id` to `top_left` (absolute).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `id` is not less than `N`.
+    pub fn move_to(&mut self, id: usize, top_left: Point) {
+        if self.windows[id].area.top_left == top_left {
+            return;
+        }
+        if self.windows[id].open {
+            let area = Rectangle::new(top_left, self.windows[id].area.size);
+            self.invalidate_below(id, Some(area));
+        }
+        self.windows[id].area.top_left = top_left;
+        self.windows[id].invalid = true;
+    }
+
+    /// Returns the topmost open window containing the (absolute) `point`, if any.
+    pub fn topmost_at(&self, point: Point) -> Option<usize> {
+        self.windows
+            .iter()
+            .enumerate()
+            .filter(|(_, window)| window.open && window.area.contains(point))
+            .max_by_key(|(_, window)| window.z)
+            .map(|(id, _)| id)
+    }
+
+    /// Shows the window `id` with the given title, with `f` adding its content.
+    ///
+    /// `f` gets the [Ui] of the window's content, and whether the window was invalidated (so everything has to
+    /// be redrawn, see the [module docs](crate::window)). Nothing is shown if the window is closed.
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the whole window, including the title bar. It's marked as redrawn if the window was invalidated.
+    /// The error is [GuiError::BoundsError] if `id` is not less than `N`.
+    pub fn window<DRAW, COL, F>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        id: usize,
+        title: &str,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, bool) -> GuiResult<()>,
+    {
+        self.show_window(ui, id, title, f)
+    }
+
+    /// Shows the window `id` with the given title, with `f` adding its content using the given smartstates.
+    ///
+    /// If the window was invalidated, the remaining smartstates are forced to redraw, so the window should use
+    /// its own [SmartstateProvider] (or come last in a shared one).
+    ///
+    /// ## Returns
+    ///
+    /// A [Response] for the whole window, including the title bar. It's marked as redrawn if the window was invalidated.
+    /// The error is [GuiError::BoundsError] if `id` is not less than `N`.
+    pub fn window_with_smartstates<DRAW, COL, F, const S: usize>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        id: usize,
+        title: &str,
+        smartstates: &mut SmartstateProvider<S>,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, &mut SmartstateProvider<S>) -> GuiResult<()>,
+    {
+        self.show_window(ui, id, title, |ui, invalid| {
+            if invalid {
+                smartstates.force_redraw_remaining();
+            }
+            f(ui, smartstates)
+        })
+    }
+
+    /// Shows the main view below all windows, with `f` adding its content.
+    ///
+    /// `f` gets a [Ui] covering the whole [Ui], and whether the main view was invalidated (so everything has to
+    /// be redrawn). The main view only gets input that isn't on top of a window.
+    pub fn background<DRAW, COL, F>(&mut self, ui: &mut Ui<DRAW, COL>, f: F) -> GuiResult<()>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, bool) -> GuiResult<()>,
+    {
+        self.show_background(ui, f)
+    }
+
+    /// Shows the main view below all windows, with `f` adding its content using the given smartstates.
+    ///
+    /// If the main view was invalidated, the remaining smartstates are forced to redraw.
+    pub fn background_with_smartstates<DRAW, COL, F, const S: usize>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        smartstates: &mut SmartstateProvider<S>,
+        f: F,
+    ) -> GuiResult<()>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, &mut SmartstateProvider<S>) -> GuiResult<()>,
+    {
+        self.show_background(ui, |ui, invalid| {
+            if invalid {
+                smartstates.force_redraw_remaining();
+            }
+            f(ui, smartstates)
+        })
+    }
+
+    fn show_background<DRAW, COL, F>(&mut self, ui: &mut Ui<DRAW, COL>, f: F) -> GuiResult<()>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, bool) -> GuiResult<()>,
+    {
+        let interact = self.drag.is_none()
+            && ui
+                .interaction()
+                .get_point()
+                .map_or(true, |point| self.topmost_at(point).is_none());
+        let excluded = self.areas_above(None);
+
+        let invalid = self.background_invalid;
+        self.background_invalid = false;
+        let damage = core::mem::take(&mut self.background_damage);
+        ui.layer_ui(&excluded, interact, |ui| {
+            if invalid {
+                ui.clear_area(ui.bounds())?;
+            } else {
+                for area in &damage {
+                    ui.clear_area(*area)?;
+                }
+            }
+            f(ui, invalid || !damage.is_empty())
+        })
+    }
+
+    fn show_window<DRAW, COL, F>(
+        &mut self,
+        ui: &mut Ui<DRAW, COL>,
+        id: usize,
+        title: &str,
+        f: F,
+    ) -> GuiResult<Response>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+        F: FnOnce(&mut Ui<DRAW, COL>, bool) -> GuiResult<()>,
+    {
+        let Some(window) = self.windows.get(id) else {
+            return Err(GuiError::BoundsError);
+        };
+        if !window.open {
+            return Ok(Response::new(InternalResponse::empty()).set_redraw(false));
+        }
+
+        let style = *ui.style();
+        let title_bar_height =
+            style.default_font.character_size.height + 2 * style.spacing.default_padding.height;
+
+        // input goes to the dragged window, or to the topmost window under the pointer
+        let interaction = ui.interaction();
+        let has_input = match (self.drag, interaction.get_point()) {
+            (Some((dragged, _)), _) => dragged == id,
+            (None, Some(point)) => self.topmost_at(point) == Some(id),
+            (None, None) => false,
+        };
+        if has_input {
+            self.handle_input(ui, id, interaction, title_bar_height);
+        }
+        let dragging = self.drag.is_some_and(|(dragged, _)| dragged == id);
+
+        let window = self.windows[id];
+        let excluded = self.areas_above(Some(id));
+        self.windows[id].invalid = false;
+
+        let border = max(style.border_width, 1);
+        let title_bar = Rectangle::new(
+            window.area.top_left,
+            Size::new(window.area.size.width, title_bar_height + border),
+        );
+        let content = Rectangle::new(
+            window.area.top_left + Point::new(border as i32, title_bar.size.height as i32),
+            window
+                .area
+                .size
+                .saturating_sub(Size::new(2 * border, title_bar.size.height + border)),
+        );
+
+        ui.layer_ui(&excluded, has_input && !dragging, |ui| {
+            if window.invalid {
+                Self::draw_frame(ui, window.area, title_bar, border, title)?;
+            }
+            ui.unchecked_sub_ui(content, |ui| f(ui, window.invalid))
+        })?;
+
+        let interaction = if has_input {
+            interaction
+        } else {
+            Interaction::None
+        };
+        Ok(
+            Response::new(InternalResponse::new(window.area, interaction))
+                .set_redraw(window.invalid),
+        )
+    }
+
+    /// Raises the window on click, and moves it while its title bar is dragged
+    fn handle_input<DRAW, COL>(
+        &mut self,
+        ui: &Ui<DRAW, COL>,
+        id: usize,
+        interaction: Interaction,
+        title_bar_height: u32,
+    ) where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let area = self.windows[id].area;
+        match interaction {
+            Interaction::Click(point) => {
+                self.raise(id);
+                self.drag = (point.y < area.top_left.y + title_bar_height as i32)
+                    .then(|| (id, point - area.top_left));
+            }
+            Interaction::Drag(point) => {
+                if let Some((_, grab)) = self.drag {
+                    // keep the window inside of the ui, if it fits
+                    let bounds = ui.bounds();
+                    let clamp = |pos: i32, start: i32, len: u32, size: u32| {
+                        if size <= len {
+                            pos.clamp(start, start + (len - size) as i32)
+                        } else {
+                            pos
+                        }
+                    };
+                    let top_left = point - grab;
+                    let top_left = Point::new(
+                        clamp(
+                            top_left.x,
+                            bounds.top_left.x,
+                            bounds.size.width,
+                            area.size.width,
+                        ),
+                        clamp(
+                            top_left.y,
+                            bounds.top_left.y,
+                            bounds.size.height,
+                            area.size.height,
+                        ),
+                    );
+                    self.move_to(id, top_left);
+                }
+            }
+            // anything else ends the drag, e.g. a swipe or the release of a long press
+            // (see crate::gesture::GestureRecognizer)
+            _ => self.drag = None,
+        }
+    }
+
+    /// Draws the border, the title bar and the background of a window
+    fn draw_frame<DRAW, COL>(
+        ui: &mut Ui<DRAW, COL>,
+        area: Rectangle,
+        title_bar: Rectangle,
+        border: u32,
+        title: &str,
+    ) -> GuiResult<()>
+    where
+        DRAW: DrawTarget<Color = COL>,
+        COL: PixelColor,
+    {
+        let style = *ui.style();
+        ui.draw(
+            &area.into_styled(
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(style.border_color)
+                    .stroke_width(border)
+                    .fill_color(style.background_color)
+                    .build(),
+            ),
+        )
+        .map_err(|_| GuiError::DrawError(Some("Couldn't draw window frame")))?;
+        ui.draw(
+            &title_bar.into_styled(
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(style.border_color)
+                    .stroke_width(border)
+                    .fill_color(style.primary_color)
+                    .build(),
+            ),
+        )
+        .map_err(|_| GuiError::DrawError(Some("Couldn't draw window title bar")))?;
+
+        let mut text = Text::new(
+            title,
+            title_bar.top_left
+                + Point::new(
+                    (border + style.spacing.default_padding.width) as i32,
+                    (border + style.spacing.default_padding.height) as i32,
+                ),
+            MonoTextStyle::new(&style.default_font, style.text_color),
+        );
+        text.text_style.baseline = Baseline::Top;
+        ui.draw(&text)
+            .map_err(|_| GuiError::DrawError(Some("Couldn't draw window title")))
+    }
+
+    /// Returns the areas of all open windows above the window `id` (or all open windows, for the main view)
+    fn areas_above(&self, id: Option<usize>) -> Vec<Rectangle, N> {
+        let z = id.map(|id| self.windows[id].z);
+        self.windows
+            .iter()
+            .enumerate()
+            .filter(|(other, window)| {
+                window.open && Some(*other) != id && z.map_or(true, |z| window.z > z)
+            })
+            .map(|(_, window)| window.area)
+            .collect()
+    }
+
+    /// Invalidates the parts of all windows below the window `id` (and of the main view) that it moves away from,
+    /// when its area changes to `new_area` (or it's closed, if `None`)
+    fn invalidate_below(&mut self, id: usize, new_area: Option<Rectangle>) {
+        let moved = self.windows[id];
+        let vacated = vacated(moved.area, new_area.unwrap_or(Rectangle::zero()));
+        let vacated = vacated.iter().filter(|area| !area.is_zero_sized());
+
+        for area in vacated.clone() {
+            for window in self.windows.iter_mut() {
+                if window.open
+                    && window.z < moved.z
+                    && !window.area.intersection(area).is_zero_sized()
+                {
+                    window.invalid = true;
+                }
+            }
+            if self.background_damage.push(*area).is_err() {
+                // too many separate areas, so clear all of them at once
+                let all = self
+                    .background_damage
+                    .iter()
+                    .fold(*area, |all, damage| bounding_box(all, *damage));
+                self.background_damage.clear();
+                self.background_damage.push(all).ok();
+            }
+        }
+    }
+}
+
+/// Maximum number of separate areas of the main view that are cleared in one frame
+const MAX_DAMAGE: usize = 8;
+
+/// Returns the parts of `old` that aren't covered by `new` (some of them may be zero-sized)
+fn vacated(old: Rectangle, new: Rectangle) -> [Rectangle; 4] {
+    let overlap = old.intersection(&new);
+    if overlap.is_zero_sized() {
+        return [old, Rectangle::zero(), Rectangle::zero(), Rectangle::zero()];
+    }
+    let old_end = old.top_left + old.size;
+    let overlap_end = overlap.top_left + overlap.size;
+    let rect = |top_left: Point, end: Point| {
+        Rectangle::new(
+            top_left,
+            Size::new(
+                (end.x - top_left.x).max(0) as u32,
+                (end.y - top_left.y).max(0) as u32,
+            ),
+        )
+    };
+    [
+        // above and below the overlap, over the full width
+        rect(old.top_left, Point::new(old_end.x, overlap.top_left.y)),
+        rect(Point::new(old.top_left.x, overlap_end.y), old_end),
+        // left and right of the overlap
+        rect(
+            Point::new(old.top_left.x, overlap.top_left.y),
+            Point::new(overlap.top_left.x, overlap_end.y),
+        ),
+        rect(
+            Point::new(overlap_end.x, overlap.top_left.y),
+            Point::new(old_end.x, overlap_end.y),
+        ),
+    ]
+}
+
+/// Returns the smallest rectangle containing both `a` and `b`
+fn bounding_box(a: Rectangle, b: Rectangle) -> Rectangle {
+    let top_left = a.top_left.component_min(b.top_left);
+    let end = (a.top_left + a.size).component_max(b.top_left + b.size);
+    Rectangle::new(
+        top_left,
+        Size::new((end.x - top_left.x) as u32, (end.y - top_left.y) as u32),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gesture::GestureRecognizer;
+    use crate::spacer::Spacer;
+    use crate::style::medsize_rgb565_style;
+    use crate::ui::Direction;
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    /// Two overlapping windows, `1` on top of `0`
+    fn windows() -> WindowManager<2> {
+        let mut windows = WindowManager::new();
+        windows.open(0, Rectangle::new(Point::new(0, 0), Size::new(40, 40)));
+        windows.open(1, Rectangle::new(Point::new(20, 20), Size::new(40, 40)));
+        windows
+    }
+
+    /// Shows the main view and both windows, returning whether each of them got input and was redrawn
+    fn frame(windows: &mut WindowManager<2>, interaction: Interaction) -> ([bool; 3], [bool; 3]) {
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        ui.interact(interaction);
+
+        let mut input = [false; 3];
+        let mut redrawn = [false; 3];
+        windows
+            .background(&mut ui, |ui, invalid| {
+                input[0] = ui.interaction() != Interaction::None;
+                redrawn[0] = invalid;
+                Ok(())
+            })
+            .unwrap();
+        for id in 0..2 {
+            windows
+                .window(&mut ui, id, "W", |ui, invalid| {
+                    input[id + 1] = ui.interaction() != Interaction::None;
+                    redrawn[id + 1] = invalid;
+                    Ok(())
+                })
+                .unwrap();
+        }
+        (input, redrawn)
+    }
+
+    #[test]
+    fn test_input_goes_to_topmost_window() {
+        let mut windows = windows();
+        frame(&mut windows, Interaction::None);
+
+        // in the overlap
+        let (input, _) = frame(&mut windows, Interaction::Hover(Point::new(30, 30)));
+        assert_eq!(input, [false, false, true]);
+        // only in the lower window
+        let (input, _) = frame(&mut windows, Interaction::Hover(Point::new(5, 30)));
+        assert_eq!(input, [false, true, false]);
+        // outside of all windows
+        let (input, _) = frame(&mut windows, Interaction::Hover(Point::new(5, 60)));
+        assert_eq!(input, [true, false, false]);
+    }
+
+    #[test]
+    fn test_invalid_id_is_an_error() {
+        let mut windows = windows();
+        let mut display = MockDisplay::<Rgb565>::new();
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        let mut smartstates = SmartstateProvider::<2>::new();
+
+        assert_eq!(
+            windows.window(&mut ui, 2, "W", |_, _| Ok(())).err(),
+            Some(GuiError::BoundsError)
+        );
+        assert_eq!(
+            windows
+                .window_with_smartstates(&mut ui, 2, "W", &mut smartstates, |_, _| Ok(()))
+                .err(),
+            Some(GuiError::BoundsError)
+        );
+    }
+
+    #[test]
+    fn test_lower_window_doesnt_draw_over_upper() {
+        let mut windows = windows();
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+
+        // the upper window is drawn first
+        windows.window(&mut ui, 1, "W", |_, _| Ok(())).unwrap();
+        windows.window(&mut ui, 0, "W", |_, _| Ok(())).unwrap();
+
+        // the title bar of the upper window is still there
+        let primary = medsize_rgb565_style().primary_color;
+        assert_eq!(display.get_pixel(Point::new(30, 25)), Some(primary));
+        assert_eq!(display.get_pixel(Point::new(10, 5)), Some(primary));
+    }
+
+    #[test]
+    fn test_click_raises_window() {
+        let mut windows = windows();
+        frame(&mut windows, Interaction::None);
+
+        let (input, redrawn) = frame(&mut windows, Interaction::Click(Point::new(5, 30)));
+        assert_eq!(input, [false, true, false]);
+        // the raised window is redrawn completely
+        assert_eq!(redrawn, [false, true, false]);
+
+        // now, the overlap belongs to the raised window
+        assert_eq!(windows.topmost_at(Point::new(30, 30)), Some(0));
+        let (input, _) = frame(&mut windows, Interaction::Hover(Point::new(30, 30)));
+        assert_eq!(input, [false, true, false]);
+    }
+
+    #[test]
+    fn test_close_invalidates_lower_windows() {
+        let mut windows = WindowManager::<3>::new();
+        windows.open(0, Rectangle::new(Point::new(0, 0), Size::new(30, 30)));
+        windows.open(1, Rectangle::new(Point::new(40, 40), Size::new(20, 20)));
+        windows.open(2, Rectangle::new(Point::new(10, 10), Size::new(20, 20)));
+
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let mut ui = Ui::new_fullscreen(&mut display, medsize_rgb565_style());
+        let show = |windows: &mut WindowManager<3>, ui: &mut Ui<_, _>| {
+            let mut redrawn = [false; 3];
+            for (id, redrawn) in redrawn.iter_mut().enumerate() {
+                *redrawn = windows
+                    .window(ui, id, "W", |ui, _| {
+                        ui.add(Spacer::new(Size::new(1, 1)));
+                        Ok(())
+                    })
+                    .unwrap()
+                    .redrawn();
+            }
+            redrawn
+        };
+
+        assert_eq!(show(&mut windows, &mut ui), [true; 3]);
+        assert_eq!(show(&mut windows, &mut ui), [false; 3]);
+
+        // only the window below the closed one is redrawn
+        windows.close(2);
+        assert_eq!(show(&mut windows, &mut ui), [true, false, false]);
+        assert_eq!(show(&mut windows, &mut ui), [false; 3]);
+    }
+
+    #[test]
+    fn test_drag_title_bar() {
+        let mut windows = windows();
+        frame(&mut windows, Interaction::None);
+
+        // grab the title bar of the upper window, and move it to the left
+        frame(&mut windows, Interaction::Click(Point::new(30, 22)));
+        let (input, redrawn) = frame(&mut windows, Interaction::Drag(Point::new(25, 22)));
+        // the content doesn't get the input while dragging
+        assert_eq!(input, [false; 3]);
+        // the lower window and the main view are redrawn where the window was (in the next frame)
+        assert_eq!(redrawn, [false, false, true]);
+        assert_eq!(
+            windows.area(1),
+            Some(Rectangle::new(Point::new(15, 20), Size::new(40, 40)))
+        );
+        // the lower window isn't below the strip the window moved away from
+        let (_, redrawn) = frame(&mut windows, Interaction::Release(Point::new(25, 22)));
+        assert_eq!(redrawn, [true, false, false]);
+
+        // moving back to the right uncovers the lower window
+        frame(&mut windows, Interaction::Click(Point::new(25, 22)));
+        frame(&mut windows, Interaction::Drag(Point::new(30, 22)));
+        let (_, redrawn) = frame(&mut windows, Interaction::Release(Point::new(30, 22)));
+        assert_eq!(redrawn, [true, true, false]);
+
+        // the window stays inside of the ui
+        frame(&mut windows, Interaction::Click(Point::new(30, 22)));
+        frame(&mut windows, Interaction::Drag(Point::new(100, 22)));
+        assert_eq!(windows.area(1).unwrap().top_left, Point::new(21, 20));
+    }
+
+    #[test]
+    fn test_move_clears_only_vacated_area() {
+        let mut windows = WindowManager::<1>::new();
+        windows.open(0, Rectangle::new(Point::new(20, 20), Size::new(20, 20)));
+        let mut display = MockDisplay::<Rgb565>::new();
+        display.set_allow_overdraw(true);
+        let style = medsize_rgb565_style();
+        let show = |windows: &mut WindowManager<1>, display: &mut MockDisplay<Rgb565>| {
+            let mut ui = Ui::new_fullscreen(display, style);
+            let mut invalid = false;
+            windows
+                .background(&mut ui, |_, background_invalid| {
+                    invalid = background_invalid;
+                    Ok(())
+                })
+                .unwrap();
+            windows.window(&mut ui, 0, "W", |_, _| Ok(())).unwrap();
+            invalid
+        };
+        assert!(show(&mut windows, &mut display));
+        assert!(!show(&mut windows, &mut display));
+
+        // something drawn by the main view, away from the window
+        display.set_pixel(Point::new(5, 5), Some(Rgb565::RED));
+
+        windows.move_to(0, Point::new(25, 20));
+        assert!(show(&mut windows, &mut display));
+        // only the strip the window left is cleared
+        assert_eq!(display.get_pixel(Point::new(5, 5)), Some(Rgb565::RED));
+        assert_eq!(
+            display.get_pixel(Point::new(22, 30)),
+            Some(style.background_color)
+        );
+        assert_ne!(
+            display.get_pixel(Point::new(30, 30)),
+            Some(style.background_color)
+        );
+
+        // nothing to redraw if the window stays where it is
+        windows.move_to(0, Point::new(25, 20));
+        assert!(!show(&mut windows, &mut display));
+        windows.open(0, Rectangle::new(Point::new(25, 20), Size::new(20, 20)));
+        assert!(!show(&mut windows, &mut display));
+    }
+
+    #[test]
+    fn test_gesture_ends_drag() {
+        let mut windows = windows();
+        frame(&mut windows, Interaction::None);
+        let mut gestures = GestureRecognizer::new();
+        // feeds a touch sample through the recognizer, returning its interaction and which views got input
+        let mut touch = |windows: &mut WindowManager<2>, time: u32, point: Option<Point>| {
+            let interaction = gestures.update(time, point);
+            (interaction, frame(windows, interaction).0)
+        };
+
+        // a fast drag of the title bar ends in a swipe instead of a release
+        touch(&mut windows, 0, Some(Point::new(30, 22)));
+        touch(&mut windows, 20, Some(Point::new(40, 22)));
+        touch(&mut windows, 40, Some(Point::new(60, 22)));
+        let (swipe, _) = touch(&mut windows, 60, None);
+        assert!(matches!(swipe, Interaction::Swipe(Direction::Right, _)));
+        let moved = windows.area(1);
+
+        // a later touch outside of the window goes to the main view, and doesn't move the window
+        assert_eq!(
+            touch(&mut windows, 1000, Some(Point::new(60, 60))).1,
+            [true, false, false]
+        );
+        assert_eq!(
+            touch(&mut windows, 1020, Some(Point::new(60, 62))).1,
+            [true, false, false]
+        );
+        assert_eq!(windows.area(1), moved);
+        touch(&mut windows, 1040, None);
+
+        // the same after a long press on the title bar, whose release emits no interaction
+        touch(&mut windows, 2000, Some(Point::new(30, 22)));
+        let (long_press, _) = touch(&mut windows, 2600, Some(Point::new(30, 22)));
+        assert!(matches!(long_press, Interaction::LongPress(_)));
+        assert_eq!(touch(&mut windows, 2620, None).0, Interaction::None);
+        assert_eq!(
+            touch(&mut windows, 3000, Some(Point::new(60, 60))).1,
+            [true, false, false]
+        );
+        assert_eq!(
+            touch(&mut windows, 3020, Some(Point::new(60, 62))).1,
+            [true, false, false]
+        );
+        assert_eq!(windows.area(1), moved);
+    }
+}